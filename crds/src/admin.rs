@@ -1,8 +1,10 @@
+use crate::role_reference::RoleReference;
 use crate::sftpgo_server_reference::ServerReference;
-use crate::{SftpgoStatus, SftpgoUserStatus};
+use crate::{FieldManagement, ReconcileState, RequestState, SftpgoStatus, SftpgoUserStatus};
 use kube::CustomResource;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
 
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -31,31 +33,50 @@ pub enum AdminPermission {
     ManageIpLists,
 }
 
-impl ToString for AdminPermission {
-    fn to_string(&self) -> String {
-        match self {
-            AdminPermission::All => "*".to_string(),
-            AdminPermission::AddUsers => "add_users".to_string(),
-            AdminPermission::EditUsers => "edit_users".to_string(),
-            AdminPermission::DelUsers => "del_users".to_string(),
-            AdminPermission::ViewUsers => "view_users".to_string(),
-            AdminPermission::ViewConns => "view_conns".to_string(),
-            AdminPermission::CloseConns => "close_conns".to_string(),
-            AdminPermission::ViewStatus => "view_status".to_string(),
-            AdminPermission::ManageAdmins => "manage_admins".to_string(),
-            AdminPermission::ManageGroups => "manage_groups".to_string(),
-            AdminPermission::ManageApikeys => "manage_apikeys".to_string(),
-            AdminPermission::QuotaScans => "quota_scans".to_string(),
-            AdminPermission::ManageSystem => "manage_system".to_string(),
-            AdminPermission::ManageDefender => "manage_defender".to_string(),
-            AdminPermission::ViewDefender => "view_defender".to_string(),
-            AdminPermission::RetentionChecks => "retention_checks".to_string(),
-            AdminPermission::MetadataChecks => "metadata_checks".to_string(),
-            AdminPermission::ViewEvents => "view_events".to_string(),
-            AdminPermission::ManageEventRules => "manage_event_rules".to_string(),
-            AdminPermission::ManageRoles => "manage_roles".to_string(),
-            AdminPermission::ManageIpLists => "manage_ip_lists".to_string(),
-        }
+impl Display for AdminPermission {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            AdminPermission::All => "*",
+            AdminPermission::AddUsers => "add_users",
+            AdminPermission::EditUsers => "edit_users",
+            AdminPermission::DelUsers => "del_users",
+            AdminPermission::ViewUsers => "view_users",
+            AdminPermission::ViewConns => "view_conns",
+            AdminPermission::CloseConns => "close_conns",
+            AdminPermission::ViewStatus => "view_status",
+            AdminPermission::ManageAdmins => "manage_admins",
+            AdminPermission::ManageGroups => "manage_groups",
+            AdminPermission::ManageApikeys => "manage_apikeys",
+            AdminPermission::QuotaScans => "quota_scans",
+            AdminPermission::ManageSystem => "manage_system",
+            AdminPermission::ManageDefender => "manage_defender",
+            AdminPermission::ViewDefender => "view_defender",
+            AdminPermission::RetentionChecks => "retention_checks",
+            AdminPermission::MetadataChecks => "metadata_checks",
+            AdminPermission::ViewEvents => "view_events",
+            AdminPermission::ManageEventRules => "manage_event_rules",
+            AdminPermission::ManageRoles => "manage_roles",
+            AdminPermission::ManageIpLists => "manage_ip_lists",
+        };
+
+        write!(f, "{}", value)
+    }
+}
+
+impl AdminPermission {
+    /// Admins with a role are not allowed to have these permissions, as they would allow them to
+    /// escape the role.
+    pub fn is_allowed_for_role_admin(&self) -> bool {
+        !matches!(
+            self,
+            AdminPermission::All
+                | AdminPermission::ManageAdmins
+                | AdminPermission::ManageApikeys
+                | AdminPermission::ManageSystem
+                | AdminPermission::ManageEventRules
+                | AdminPermission::ManageRoles
+                | AdminPermission::ManageIpLists
+        )
     }
 }
 
@@ -75,7 +96,13 @@ pub struct SftpgoAdminConfiguration {
     /// If set the admin can only administer users with the same role. Role admins cannot have the
     /// following permissions: "manage_admins", "manage_apikeys", "manage_system",
     /// "manage_event_rules", "manage_roles", "manage_ip_lists"
+    ///
+    /// The role must already exist on the server. Use roleReference to refer to a role managed
+    /// by a SftpgoRole resource instead.
     pub role: Option<String>,
+    /// Reference to a SftpgoRole resource. Cannot be combined with role. The same permission
+    /// restrictions as for role applies.
+    pub role_reference: Option<RoleReference>,
 }
 
 #[derive(CustomResource, Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema)]
@@ -86,6 +113,7 @@ pub struct SftpgoAdminConfiguration {
     plural = "sftpgoadmins",
     derive = "PartialEq",
     status = "SftpgoAdminResourceStatus",
+    printcolumn = r#"{"name":"Ready", "type":"string", "description":"Whether the resource is reconciled", "jsonPath":".status.conditions[?(@.type==\"Ready\")].status"}"#,
     printcolumn = r#"{"name":"Admin id", "type":"string", "description":"Id of the admin", "jsonPath":".status.admin_id"}"#,
     namespaced
 )]
@@ -93,17 +121,28 @@ pub struct SftpgoAdminConfiguration {
 pub struct SftpgoAdminSpec {
     pub configuration: SftpgoAdminConfiguration,
 
+    /// Whether the resource in SFTPGo is replaced, or only the configured fields are changed.
+    /// Defaults to Full
+    pub field_management: Option<FieldManagement>,
     #[serde(rename = "sftpgoServerReference")]
     pub server_reference: ServerReference,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema, Default)]
 pub struct SftpgoAdminResourceStatus {
+    /// The name in SFTPGo, empty until the resource was created there
+    #[serde(default)]
     pub last_username: String,
     pub admin_id: Option<i32>,
+    #[serde(flatten)]
+    pub request: RequestState,
+    #[serde(flatten)]
+    pub reconcile: ReconcileState,
 }
 
 impl SftpgoStatus for SftpgoAdminResourceStatus {
+    type Id = i32;
+
     fn get_last_name(&self) -> &str {
         &self.last_username
     }
@@ -112,11 +151,11 @@ impl SftpgoStatus for SftpgoAdminResourceStatus {
         self.last_username = name.to_string();
     }
 
-    fn get_id(&self) -> Option<i32> {
+    fn get_id(&self) -> Option<Self::Id> {
         self.admin_id
     }
 
-    fn set_id(&mut self, id: Option<i32>) {
+    fn set_id(&mut self, id: Option<Self::Id>) {
         self.admin_id = id;
     }
 }
@@ -0,0 +1,97 @@
+use crate::{ReconcileState, ServerReference, SftpgoStatus};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use kube::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub enum SftpgoApiKeyScope {
+    /// The key can be used to access the admin REST API
+    #[default]
+    Admin,
+    /// The key can be used to access the user REST API
+    User,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyPrincipalReference {
+    /// The kubernetes resource name of the SftpgoAdmin or SftpgoUser
+    pub name: String,
+    /// The kubernetes namespace the resource is defined in, if different from the namespace
+    /// of this resource.
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpgoApiKeyConfiguration {
+    /// The name of the api key. Does not have to be unique.
+    pub name: String,
+    pub scope: SftpgoApiKeyScope,
+    /// optional description
+    pub description: Option<String>,
+    /// When the key expires. If not set the key never expires
+    pub expires_at: Option<Time>,
+    /// The SftpgoAdmin the key is associated with. Only valid for the Admin scope. If not set
+    /// the key can be used to impersonate any admin.
+    pub admin_reference: Option<ApiKeyPrincipalReference>,
+    /// The SftpgoUser the key is associated with. Only valid for the User scope. If not set
+    /// the key can be used to impersonate any user.
+    pub user_reference: Option<ApiKeyPrincipalReference>,
+}
+
+#[derive(CustomResource, Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema)]
+#[kube(
+    group = "sftpgo.digizuite.com",
+    version = "v1alpha1",
+    kind = "SftpgoApiKey",
+    plural = "sftpgoapikeys",
+    derive = "PartialEq",
+    status = "SftpgoApiKeyResourceStatus",
+    printcolumn = r#"{"name":"Ready", "type":"string", "description":"Whether the resource is reconciled", "jsonPath":".status.conditions[?(@.type==\"Ready\")].status"}"#,
+    printcolumn = r#"{"name":"Key id", "type":"string", "description":"Id of the api key", "jsonPath":".status.key_id"}"#,
+    printcolumn = r#"{"name":"Secret", "type":"string", "description":"Secret containing the api key", "jsonPath":".status.secret_name"}"#,
+    namespaced
+)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpgoApiKeySpec {
+    pub configuration: SftpgoApiKeyConfiguration,
+    /// The name of the secret the generated key is written to. Defaults to
+    /// "<resource name>-api-key". SFTPGo only returns the key when it is created, so if the
+    /// secret is deleted a new key is generated.
+    pub secret_name: Option<String>,
+    #[serde(rename = "sftpgoServerReference")]
+    pub server_reference: ServerReference,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema, Default)]
+pub struct SftpgoApiKeyResourceStatus {
+    /// The name in SFTPGo, empty until the resource was created there
+    #[serde(default)]
+    pub last_name: String,
+    pub key_id: Option<String>,
+    pub secret_name: Option<String>,
+    #[serde(flatten)]
+    pub reconcile: ReconcileState,
+}
+
+impl SftpgoStatus for SftpgoApiKeyResourceStatus {
+    type Id = String;
+
+    fn get_last_name(&self) -> &str {
+        &self.last_name
+    }
+
+    fn set_last_name(&mut self, name: &str) {
+        self.last_name = name.to_string();
+    }
+
+    fn get_id(&self) -> Option<Self::Id> {
+        self.key_id.clone()
+    }
+
+    fn set_id(&mut self, id: Option<Self::Id>) {
+        self.key_id = id;
+    }
+}
@@ -0,0 +1,154 @@
+use crate::{
+    FieldManagement, ProvidedValue, ReconcileState, RequestState, ServerReference, SftpgoStatus,
+};
+use kube::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub enum HttpMethod {
+    Get,
+    #[default]
+    Post,
+    Put,
+    Delete,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EventActionHttp {
+    /// The URL to call. Placeholders such as {{VirtualPath}} are supported in the query string
+    pub endpoint: String,
+    pub method: Option<HttpMethod>,
+    pub username: Option<String>,
+    pub password: Option<ProvidedValue<String>>,
+    pub headers: Option<BTreeMap<String, String>>,
+    pub query_parameters: Option<BTreeMap<String, String>>,
+    /// The request body. Placeholders are supported
+    pub body: Option<String>,
+    /// Timeout in seconds, between 1 and 180. Defaults to 20
+    pub timeout: Option<i32>,
+    pub skip_tls_verify: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EventActionCommand {
+    /// Absolute path to the command to execute. The command must be allowed in the server
+    /// configuration.
+    pub cmd: String,
+    pub args: Option<Vec<String>>,
+    /// Timeout in seconds, between 1 and 120. Defaults to 20
+    pub timeout: Option<i32>,
+    pub env_vars: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EventActionEmail {
+    pub recipients: Vec<String>,
+    pub bcc: Option<Vec<String>>,
+    pub subject: String,
+    pub body: String,
+    /// Send the body as text/html instead of text/plain
+    pub html: Option<bool>,
+    /// Virtual paths of files to attach
+    pub attachments: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderRetention {
+    /// Virtual path to apply the retention to
+    pub path: String,
+    /// Retention time in hours. 0 excludes the path from the check
+    pub retention: i32,
+    pub delete_empty_dirs: Option<bool>,
+    pub ignore_user_permissions: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EventActionDataRetention {
+    pub folders: Vec<FolderRetention>,
+}
+
+/// What the action does. Actions without options are set to an empty object, for example
+/// `folderQuotaReset: {}`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum SftpgoEventActionKind {
+    Http(EventActionHttp),
+    Command(EventActionCommand),
+    Email(EventActionEmail),
+    DataRetentionCheck(EventActionDataRetention),
+    Backup {},
+    UserQuotaReset {},
+    FolderQuotaReset {},
+    TransferQuotaReset {},
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpgoEventActionConfiguration {
+    /// unique name for this action
+    pub name: String,
+    /// optional description
+    pub description: Option<String>,
+    pub action: SftpgoEventActionKind,
+}
+
+#[derive(CustomResource, Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema)]
+#[kube(
+    group = "sftpgo.digizuite.com",
+    version = "v1alpha1",
+    kind = "SftpgoEventAction",
+    plural = "sftpgoeventactions",
+    derive = "PartialEq",
+    status = "SftpgoEventActionResourceStatus",
+    printcolumn = r#"{"name":"Ready", "type":"string", "description":"Whether the resource is reconciled", "jsonPath":".status.conditions[?(@.type==\"Ready\")].status"}"#,
+    printcolumn = r#"{"name":"Action id", "type":"string", "description":"Id of the event action", "jsonPath":".status.action_id"}"#,
+    namespaced
+)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpgoEventActionSpec {
+    pub configuration: SftpgoEventActionConfiguration,
+    /// Whether the resource in SFTPGo is replaced, or only the configured fields are changed.
+    /// Defaults to Full
+    pub field_management: Option<FieldManagement>,
+    #[serde(rename = "sftpgoServerReference")]
+    pub server_reference: ServerReference,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema, Default)]
+pub struct SftpgoEventActionResourceStatus {
+    /// The name in SFTPGo, empty until the resource was created there
+    #[serde(default)]
+    last_name: String,
+    action_id: Option<i32>,
+    #[serde(flatten)]
+    pub request: RequestState,
+    #[serde(flatten)]
+    pub reconcile: ReconcileState,
+}
+
+impl SftpgoStatus for SftpgoEventActionResourceStatus {
+    type Id = i32;
+
+    fn get_last_name(&self) -> &str {
+        &self.last_name
+    }
+
+    fn set_last_name(&mut self, name: &str) {
+        self.last_name = name.to_string();
+    }
+
+    fn get_id(&self) -> Option<Self::Id> {
+        self.action_id
+    }
+
+    fn set_id(&mut self, id: Option<Self::Id>) {
+        self.action_id = id;
+    }
+}
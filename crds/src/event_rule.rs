@@ -0,0 +1,242 @@
+use crate::{
+    FieldManagement, ReconcileState, RequestState, ServerReference, SftpgoStatus, SftpgoUserStatus,
+};
+use kube::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum FilesystemEvent {
+    Upload,
+    PreUpload,
+    FirstUpload,
+    Download,
+    PreDownload,
+    FirstDownload,
+    Delete,
+    PreDelete,
+    Rename,
+    Mkdir,
+    Rmdir,
+    Copy,
+    Ssh,
+}
+
+impl Display for FilesystemEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            FilesystemEvent::Upload => "upload",
+            FilesystemEvent::PreUpload => "pre-upload",
+            FilesystemEvent::FirstUpload => "first-upload",
+            FilesystemEvent::Download => "download",
+            FilesystemEvent::PreDownload => "pre-download",
+            FilesystemEvent::FirstDownload => "first-download",
+            FilesystemEvent::Delete => "delete",
+            FilesystemEvent::PreDelete => "pre-delete",
+            FilesystemEvent::Rename => "rename",
+            FilesystemEvent::Mkdir => "mkdir",
+            FilesystemEvent::Rmdir => "rmdir",
+            FilesystemEvent::Copy => "copy",
+            FilesystemEvent::Ssh => "ssh_cmd",
+        };
+
+        write!(f, "{}", value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ProviderEvent {
+    Add,
+    Update,
+    Delete,
+}
+
+impl Display for ProviderEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            ProviderEvent::Add => "add",
+            ProviderEvent::Update => "update",
+            ProviderEvent::Delete => "delete",
+        };
+
+        write!(f, "{}", value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ProviderObject {
+    User,
+    Folder,
+    Group,
+    Admin,
+    ApiKey,
+    Share,
+    EventAction,
+    EventRule,
+    Role,
+}
+
+impl Display for ProviderObject {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            ProviderObject::User => "user",
+            ProviderObject::Folder => "folder",
+            ProviderObject::Group => "group",
+            ProviderObject::Admin => "admin",
+            ProviderObject::ApiKey => "api_key",
+            ProviderObject::Share => "share",
+            ProviderObject::EventAction => "event_action",
+            ProviderObject::EventRule => "event_rule",
+            ProviderObject::Role => "role",
+        };
+
+        write!(f, "{}", value)
+    }
+}
+
+/// A cron like schedule. All times are in UTC
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EventSchedule {
+    pub hour: String,
+    pub day_of_week: Option<String>,
+    pub day_of_month: Option<String>,
+    pub month: Option<String>,
+}
+
+/// When the rule is triggered
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum SftpgoEventTrigger {
+    #[serde(rename_all = "camelCase")]
+    Filesystem {
+        events: Vec<FilesystemEvent>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Provider {
+        events: Vec<ProviderEvent>,
+        objects: Option<Vec<ProviderObject>>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Schedule {
+        schedules: Vec<EventSchedule>,
+    },
+    IpBlocked {},
+    CertificateRenewal {},
+    OnDemand {},
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EventConditionPattern {
+    /// Shell like pattern, for example "*.csv"
+    pub pattern: String,
+    pub inverse_match: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpgoEventConditions {
+    /// Patterns the username, folder name or object name must match
+    pub names: Option<Vec<EventConditionPattern>>,
+    pub group_names: Option<Vec<EventConditionPattern>>,
+    pub role_names: Option<Vec<EventConditionPattern>>,
+    /// Patterns the virtual path must match. Only for filesystem events
+    pub fs_paths: Option<Vec<EventConditionPattern>>,
+    /// Only for filesystem events, for example "SFTP", "FTP", "HTTP"
+    pub protocols: Option<Vec<String>>,
+    /// Minimum file size in bytes. Only for filesystem events
+    pub min_file_size: Option<i64>,
+    /// Maximum file size in bytes. Only for filesystem events
+    pub max_file_size: Option<i64>,
+    /// Allow executing the rule concurrently from multiple instances. Only for schedules
+    pub concurrent_execution: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EventActionReference {
+    /// The kubernetes resource name of the event action
+    pub name: String,
+    /// The kubernetes namespace the event action is defined in, if different from the namespace
+    /// of this resource.
+    pub namespace: Option<String>,
+    /// The action is only executed if a previous action failed
+    pub is_failure_action: Option<bool>,
+    /// Stop executing the following actions if this action fails
+    pub stop_on_failure: Option<bool>,
+    /// Execute the action synchronously. Only for upload, pre-* and provider events
+    pub execute_sync: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpgoEventRuleConfiguration {
+    /// unique name for this rule
+    pub name: String,
+    /// optional description
+    pub description: Option<String>,
+    pub enabled: Option<SftpgoUserStatus>,
+    pub trigger: SftpgoEventTrigger,
+    pub conditions: Option<SftpgoEventConditions>,
+    /// The actions to execute, in order
+    pub actions: Vec<EventActionReference>,
+}
+
+#[derive(CustomResource, Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema)]
+#[kube(
+    group = "sftpgo.digizuite.com",
+    version = "v1alpha1",
+    kind = "SftpgoEventRule",
+    plural = "sftpgoeventrules",
+    derive = "PartialEq",
+    status = "SftpgoEventRuleResourceStatus",
+    printcolumn = r#"{"name":"Ready", "type":"string", "description":"Whether the resource is reconciled", "jsonPath":".status.conditions[?(@.type==\"Ready\")].status"}"#,
+    printcolumn = r#"{"name":"Rule id", "type":"string", "description":"Id of the event rule", "jsonPath":".status.rule_id"}"#,
+    namespaced
+)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpgoEventRuleSpec {
+    pub configuration: SftpgoEventRuleConfiguration,
+    /// Whether the resource in SFTPGo is replaced, or only the configured fields are changed.
+    /// Defaults to Full
+    pub field_management: Option<FieldManagement>,
+    #[serde(rename = "sftpgoServerReference")]
+    pub server_reference: ServerReference,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema, Default)]
+pub struct SftpgoEventRuleResourceStatus {
+    /// The name in SFTPGo, empty until the resource was created there
+    #[serde(default)]
+    last_name: String,
+    rule_id: Option<i32>,
+    #[serde(flatten)]
+    pub request: RequestState,
+    #[serde(flatten)]
+    pub reconcile: ReconcileState,
+}
+
+impl SftpgoStatus for SftpgoEventRuleResourceStatus {
+    type Id = i32;
+
+    fn get_last_name(&self) -> &str {
+        &self.last_name
+    }
+
+    fn set_last_name(&mut self, name: &str) {
+        self.last_name = name.to_string();
+    }
+
+    fn get_id(&self) -> Option<Self::Id> {
+        self.rule_id
+    }
+
+    fn set_id(&mut self, id: Option<Self::Id>) {
+        self.rule_id = id;
+    }
+}
@@ -0,0 +1,13 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// How the resource in SFTPGo is updated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+pub enum FieldManagement {
+    /// The resource is replaced, so anything not set in the configuration is reset to its default
+    #[default]
+    Full,
+    /// Only the fields set in the configuration are changed, so anything else can be managed in
+    /// the SFTPGo web admin
+    Partial,
+}
@@ -1,5 +1,6 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
@@ -10,6 +11,16 @@ pub enum ValueFrom {
     SecretKeyRef { name: String, key: String },
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretKeyReference {
+    /// The name of the secret, in the namespace of the resource
+    pub name: String,
+    pub key: String,
+}
+
+/// A value given either directly, or read from a secret or config map in the namespace of the
+/// resource. Use a secret for anything sensitive.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum ProvidedValue<T> {
@@ -26,9 +37,9 @@ pub enum AzureBlobStorageAuthorization {
         /// it exists before using it here.
         container: String,
         account_name: String,
-        account_key: String,
+        account_key: ProvidedValue<String>,
     },
-    SharedAccessSignatureUrl(String),
+    SharedAccessSignatureUrl(ProvidedValue<String>),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
@@ -38,13 +49,15 @@ pub enum AzureBlobStorageAccessTier {
     Archive,
 }
 
-impl ToString for AzureBlobStorageAccessTier {
-    fn to_string(&self) -> String {
-        match self {
-            AzureBlobStorageAccessTier::Hot => "hot".to_string(),
-            AzureBlobStorageAccessTier::Cool => "cool".to_string(),
-            AzureBlobStorageAccessTier::Archive => "archive".to_string(),
-        }
+impl Display for AzureBlobStorageAccessTier {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            AzureBlobStorageAccessTier::Hot => "hot",
+            AzureBlobStorageAccessTier::Cool => "cool",
+            AzureBlobStorageAccessTier::Archive => "archive",
+        };
+
+        write!(f, "{}", value)
     }
 }
 
@@ -79,6 +92,133 @@ pub struct FileSystemAzureBlobStorage {
     pub use_emulator: Option<bool>,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FileSystemS3 {
+    pub bucket: String,
+    pub region: String,
+    /// optional endpoint, required for S3 compatible storage such as MinIO, for example
+    /// "http://minio.minio.svc:9000"
+    pub endpoint: Option<String>,
+    /// If not set the credentials are loaded from the environment or the instance role
+    pub access_key: Option<ProvidedValue<String>>,
+    pub access_secret: Option<ProvidedValue<String>>,
+    /// optional IAM role to assume
+    pub role_arn: Option<String>,
+    /// for example "STANDARD" or "STANDARD_IA". Leave empty to use the bucket default
+    pub storage_class: Option<String>,
+    /// the canned ACL to apply to uploaded objects, for example "private". Leave empty to use
+    /// the bucket default
+    pub acl: Option<String>,
+    /// the buffer size (in MB) to use for multipart uploads. The minimum allowed part size is
+    /// 5MB. If this value is not set, the default value (5MB) will be used.
+    pub upload_part_size: Option<i32>,
+    /// the number of parts to upload in parallel. If this value is not set, the default value
+    /// (5) will be used
+    pub upload_concurrency: Option<i32>,
+    /// the maximum time allowed, in seconds, to upload a single chunk. If not set there is no
+    /// limit
+    pub upload_part_max_time: Option<i32>,
+    /// the buffer size (in MB) to use for multipart downloads. If this value is not set, the
+    /// default value (5MB) will be used.
+    pub download_part_size: Option<i32>,
+    /// the number of parts to download in parallel. If this value is not set, the default
+    /// value (5) will be used
+    pub download_concurrency: Option<i32>,
+    /// the maximum time allowed, in seconds, to download a single chunk. If not set there is
+    /// no limit
+    pub download_part_max_time: Option<i32>,
+    /// use path style addressing ("endpoint/bucket/key") instead of virtual hosted style
+    /// ("bucket.endpoint/key"). Usually required for MinIO
+    pub force_path_style: Option<bool>,
+
+    /// key_prefix is similar to a chroot directory for a local filesystem. If specified the
+    /// user will only see contents that starts with this prefix and so you can restrict access
+    /// to a specific virtual folder. The prefix, if not empty, must not start with "/" and must
+    /// end with "/". If empty the whole bucket contents will be available
+    #[schemars(regex(pattern = r"^[^\/].+\/$"))]
+    pub key_prefix: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FileSystemGoogleCloudStorage {
+    pub bucket: String,
+    /// The JSON key of the service account to use. If not set the automatic credentials of the
+    /// environment, such as workload identity, are used
+    pub credentials: Option<ProvidedValue<String>>,
+    /// for example "STANDARD" or "NEARLINE". Leave empty to use the bucket default
+    pub storage_class: Option<String>,
+    /// the predefined ACL to apply to uploaded objects, for example "projectPrivate". Leave
+    /// empty to use the bucket default
+    pub acl: Option<String>,
+    /// the buffer size (in MB) to use for uploads. If this value is not set, the default value
+    /// (16MB) will be used.
+    pub upload_part_size: Option<i32>,
+    /// the maximum time allowed, in seconds, to upload a single chunk. If not set there is no
+    /// limit
+    pub upload_part_max_time: Option<i32>,
+
+    /// key_prefix is similar to a chroot directory for a local filesystem. If specified the
+    /// user will only see contents that starts with this prefix and so you can restrict access
+    /// to a specific virtual folder. The prefix, if not empty, must not start with "/" and must
+    /// end with "/". If empty the whole bucket contents will be available
+    #[schemars(regex(pattern = r"^[^\/].+\/$"))]
+    pub key_prefix: Option<String>,
+}
+
+/// How SFTPGo decides if two users or folders point to the same remote server, for example
+/// to do server side moves between them
+#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub enum EqualityCheckMode {
+    /// The endpoint and the username must match
+    #[default]
+    EndpointAndUsername,
+    /// Only the endpoint must match
+    Endpoint,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FileSystemSftp {
+    /// The address of the remote server as "host:port", for example "sftp.example.com:22"
+    pub endpoint: String,
+    pub username: String,
+    /// At least one of password and private key must be set
+    pub password: Option<ProvidedValue<String>>,
+    pub private_key: Option<ProvidedValue<String>>,
+    /// The passphrase of the private key, if it is encrypted
+    pub key_passphrase: Option<ProvidedValue<String>>,
+    /// SHA256 fingerprints of the host keys to accept, for example
+    /// "SHA256:RFzBCUItH9LZS0cKB5UE6ceAYhBD5C8GeOBip8Z11+4". If not set any host key is accepted
+    pub fingerprints: Option<Vec<String>>,
+    /// Restricts the user to the given path on the remote server, for example "/home/user"
+    pub prefix: Option<String>,
+    /// Concurrent reads are safe to use and disabling them will degrade performance, only
+    /// disable them for servers that don't support them
+    pub disable_concurrent_reads: Option<bool>,
+    /// the size (in MB) of the buffer used for uploads and downloads, between 0 and 16. 0
+    /// disables buffering
+    pub buffer_size: Option<i32>,
+    pub equality_check_mode: Option<EqualityCheckMode>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FileSystemHttp {
+    /// The base url of a service implementing the SFTPGo HTTP filesystem API, for example
+    /// "https://storage.example.com/api/v1"
+    pub endpoint: String,
+    /// Username for basic authentication
+    pub username: Option<String>,
+    /// Password for basic authentication
+    pub password: Option<ProvidedValue<String>>,
+    /// Sent in the X-API-KEY header
+    pub api_key: Option<ProvidedValue<String>>,
+    pub skip_tls_verify: Option<bool>,
+    pub equality_check_mode: Option<EqualityCheckMode>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum FileSystem {
@@ -89,6 +229,22 @@ pub enum FileSystem {
     },
     #[serde(rename_all = "camelCase")]
     AzureBlobStorage(Box<FileSystemAzureBlobStorage>),
+    #[serde(rename_all = "camelCase")]
+    S3(Box<FileSystemS3>),
+    #[serde(rename_all = "camelCase")]
+    GoogleCloudStorage(Box<FileSystemGoogleCloudStorage>),
+    #[serde(rename_all = "camelCase")]
+    Sftp(Box<FileSystemSftp>),
+    #[serde(rename_all = "camelCase")]
+    Http(Box<FileSystemHttp>),
+    /// Local filesystem where files are encrypted at rest. The passphrase can't be changed once
+    /// the resource exists, as stored files could then no longer be decrypted.
+    #[serde(rename_all = "camelCase")]
+    LocalEncrypted {
+        passphrase: SecretKeyReference,
+        read_buffer_size: Option<i32>,
+        write_buffer_size: Option<i32>,
+    },
 }
 
 impl Default for FileSystem {
@@ -1,4 +1,6 @@
-use crate::{FileSystem, ServerReference, SftpgoStatus};
+use crate::{
+    FieldManagement, FileSystem, ReconcileState, RequestState, ServerReference, SftpgoStatus,
+};
 use kube::CustomResource;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -27,22 +29,39 @@ pub struct SftpgoFolderConfiguration {
     plural = "sftpgofolders",
     derive = "PartialEq",
     status = "SftpgoFolderResourceStatus",
+    printcolumn = r#"{"name":"Ready", "type":"string", "description":"Whether the resource is reconciled", "jsonPath":".status.conditions[?(@.type==\"Ready\")].status"}"#,
     namespaced
 )]
 #[serde(rename_all = "camelCase")]
 pub struct SftpgoFolderSpec {
     pub configuration: SftpgoFolderConfiguration,
+    /// Allow changing the passphrase of an encrypted filesystem, or turning encryption on or
+    /// off, while the resource has files in SFTPGo. Files stored before can then no longer be read
+    pub allow_passphrase_change: Option<bool>,
+    /// Whether the resource in SFTPGo is replaced, or only the configured fields are changed.
+    /// Defaults to Full
+    pub field_management: Option<FieldManagement>,
     #[serde(rename = "sftpgoServerReference")]
     pub server_reference: ServerReference,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema, Default)]
 pub struct SftpgoFolderResourceStatus {
+    /// The name in SFTPGo, empty until the resource was created there
+    #[serde(default)]
     last_name: String,
     folder_id: Option<i32>,
+    /// Hash of the passphrase of the encrypted filesystem, used to detect changes to it
+    pub passphrase_hash: Option<String>,
+    #[serde(flatten)]
+    pub request: RequestState,
+    #[serde(flatten)]
+    pub reconcile: ReconcileState,
 }
 
 impl SftpgoStatus for SftpgoFolderResourceStatus {
+    type Id = i32;
+
     fn get_last_name(&self) -> &str {
         &self.last_name
     }
@@ -51,11 +70,11 @@ impl SftpgoStatus for SftpgoFolderResourceStatus {
         self.last_name = name.to_string();
     }
 
-    fn get_id(&self) -> Option<i32> {
+    fn get_id(&self) -> Option<Self::Id> {
         self.folder_id
     }
 
-    fn set_id(&mut self, id: Option<i32>) {
+    fn set_id(&mut self, id: Option<Self::Id>) {
         self.folder_id = id;
     }
 }
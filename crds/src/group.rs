@@ -0,0 +1,96 @@
+use crate::{
+    DirectoryPermission, FieldManagement, FileSystem, ReconcileState, RequestState,
+    ServerReference, SftpgoStatus, UserPermission, VirtualFolderReference,
+};
+use kube::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Settings inherited by every user that is a member of the group. Settings defined directly on
+/// the user takes precedence over the group settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpgoGroupUserSettings {
+    /// The home dir of the members. The placeholder "%username%" is replaced with the username
+    /// of the member.
+    pub home_dir: Option<String>,
+    pub global_permissions: Option<Vec<UserPermission>>,
+    pub per_directory_permissions: Option<Vec<DirectoryPermission>>,
+    pub filesystem: Option<FileSystem>,
+    /// Maximum concurrent sessions. 0 means unlimited
+    pub max_sessions: Option<i32>,
+    /// Quota as size in bytes. 0 means unlimited
+    pub quota_size: Option<i64>,
+    /// Quota as number of files. 0 means unlimited
+    pub quota_files: Option<i32>,
+    /// Maximum upload bandwidth as KB/s. 0 means unlimited
+    pub upload_bandwidth: Option<i64>,
+    /// Maximum download bandwidth as KB/s. 0 means unlimited
+    pub download_bandwidth: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpgoGroupConfiguration {
+    /// unique name for this group
+    pub name: String,
+    /// optional description
+    pub description: Option<String>,
+    pub user_settings: Option<SftpgoGroupUserSettings>,
+    /// Virtual folders available to all members of the group
+    pub virtual_folders: Option<Vec<VirtualFolderReference>>,
+}
+
+#[derive(CustomResource, Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema)]
+#[kube(
+    group = "sftpgo.digizuite.com",
+    version = "v1alpha1",
+    kind = "SftpgoGroup",
+    plural = "sftpgogroups",
+    derive = "PartialEq",
+    status = "SftpgoGroupResourceStatus",
+    printcolumn = r#"{"name":"Ready", "type":"string", "description":"Whether the resource is reconciled", "jsonPath":".status.conditions[?(@.type==\"Ready\")].status"}"#,
+    printcolumn = r#"{"name":"Group id", "type":"string", "description":"Id of the group", "jsonPath":".status.group_id"}"#,
+    namespaced
+)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpgoGroupSpec {
+    pub configuration: SftpgoGroupConfiguration,
+    /// Whether the resource in SFTPGo is replaced, or only the configured fields are changed.
+    /// Defaults to Full
+    pub field_management: Option<FieldManagement>,
+    #[serde(rename = "sftpgoServerReference")]
+    pub server_reference: ServerReference,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema, Default)]
+pub struct SftpgoGroupResourceStatus {
+    /// The name in SFTPGo, empty until the resource was created there
+    #[serde(default)]
+    last_name: String,
+    group_id: Option<i32>,
+    #[serde(flatten)]
+    pub request: RequestState,
+    #[serde(flatten)]
+    pub reconcile: ReconcileState,
+}
+
+impl SftpgoStatus for SftpgoGroupResourceStatus {
+    type Id = i32;
+
+    fn get_last_name(&self) -> &str {
+        &self.last_name
+    }
+
+    fn set_last_name(&mut self, name: &str) {
+        self.last_name = name.to_string();
+    }
+
+    fn get_id(&self) -> Option<Self::Id> {
+        self.group_id
+    }
+
+    fn set_id(&mut self, id: Option<Self::Id>) {
+        self.group_id = id;
+    }
+}
@@ -0,0 +1,26 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub enum SftpgoGroupType {
+    /// The user inherits the group settings. A user can only have one primary group.
+    #[default]
+    Primary,
+    /// The user inherits the virtual folders and the permissions of the group.
+    Secondary,
+    /// The group is only used for membership, nothing is inherited.
+    Membership,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupReference {
+    /// The kubernetes resource name of the group
+    pub name: String,
+    /// The kubernetes namespace the group is defined in, if different from the namespace
+    /// of this resource.
+    pub namespace: Option<String>,
+    /// How the user is related to the group. Defaults to Primary
+    #[serde(rename = "type")]
+    pub group_type: Option<SftpgoGroupType>,
+}
@@ -0,0 +1,73 @@
+use crate::{ReconcileState, ServerReference};
+use kube::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub enum SftpgoIpListType {
+    /// Only clients in the allow list can connect, if the allow list is enabled in the server
+    Allowlist,
+    /// Entries in the defender list are always allowed or always banned by the defender
+    Defender,
+    /// Entries in the safe list are not rate limited
+    RateLimiterSafeList,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub enum SftpgoIpListMode {
+    #[default]
+    Allow,
+    /// Only valid for the Defender list
+    Deny,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub enum IpListProtocol {
+    Ssh,
+    Ftp,
+    WebDav,
+    Http,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpgoIpListEntryConfiguration {
+    pub list_type: SftpgoIpListType,
+    /// IP address or network in CIDR notation, for example "192.168.1.0/24"
+    pub ip_or_net: String,
+    pub mode: Option<SftpgoIpListMode>,
+    /// The protocols the entry applies to. If not set the entry applies to all protocols
+    pub protocols: Option<Vec<IpListProtocol>>,
+    /// optional description
+    pub description: Option<String>,
+}
+
+#[derive(CustomResource, Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema)]
+#[kube(
+    group = "sftpgo.digizuite.com",
+    version = "v1alpha1",
+    kind = "SftpgoIpListEntry",
+    plural = "sftpgoiplistentries",
+    derive = "PartialEq",
+    status = "SftpgoIpListEntryResourceStatus",
+    printcolumn = r#"{"name":"Ready", "type":"string", "description":"Whether the resource is reconciled", "jsonPath":".status.conditions[?(@.type==\"Ready\")].status"}"#,
+    printcolumn = r#"{"name":"List", "type":"string", "description":"The list the entry is part of", "jsonPath":".spec.configuration.listType"}"#,
+    printcolumn = r#"{"name":"Ip or net", "type":"string", "description":"The ip or network of the entry", "jsonPath":".spec.configuration.ipOrNet"}"#,
+    namespaced
+)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpgoIpListEntrySpec {
+    pub configuration: SftpgoIpListEntryConfiguration,
+    #[serde(rename = "sftpgoServerReference")]
+    pub server_reference: ServerReference,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema, Default)]
+pub struct SftpgoIpListEntryResourceStatus {
+    /// The list the entry was last created in
+    pub list_type: Option<SftpgoIpListType>,
+    /// The ip or network the entry was last created with
+    pub ip_or_net: Option<String>,
+    #[serde(flatten)]
+    pub reconcile: ReconcileState,
+}
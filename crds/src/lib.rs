@@ -1,17 +1,39 @@
 mod admin;
+mod api_key;
+mod event_action;
+mod event_rule;
+mod field_management;
 mod filesystem;
 mod folder;
+mod group;
+mod group_reference;
+mod ip_list_entry;
+mod role;
+mod role_reference;
 mod server;
 mod sftpgo_server_reference;
 mod sftpgo_status;
+mod share;
 mod user;
+mod user_reference;
 mod virtual_folder_reference;
 
 pub use admin::*;
+pub use api_key::*;
+pub use event_action::*;
+pub use event_rule::*;
+pub use field_management::*;
 pub use filesystem::*;
 pub use folder::*;
+pub use group::*;
+pub use group_reference::*;
+pub use ip_list_entry::*;
+pub use role::*;
+pub use role_reference::*;
 pub use server::*;
 pub use sftpgo_server_reference::*;
 pub use sftpgo_status::*;
+pub use share::*;
 pub use user::*;
+pub use user_reference::*;
 pub use virtual_folder_reference::*;
@@ -0,0 +1,69 @@
+use crate::{FieldManagement, ReconcileState, RequestState, ServerReference, SftpgoStatus};
+use kube::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Roles can be assigned to users and admins. An admin with a role can only administer users
+/// with the same role.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpgoRoleConfiguration {
+    /// unique name for this role
+    pub name: String,
+    /// optional description
+    pub description: Option<String>,
+}
+
+#[derive(CustomResource, Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema)]
+#[kube(
+    group = "sftpgo.digizuite.com",
+    version = "v1alpha1",
+    kind = "SftpgoRole",
+    plural = "sftpgoroles",
+    derive = "PartialEq",
+    status = "SftpgoRoleResourceStatus",
+    printcolumn = r#"{"name":"Ready", "type":"string", "description":"Whether the resource is reconciled", "jsonPath":".status.conditions[?(@.type==\"Ready\")].status"}"#,
+    printcolumn = r#"{"name":"Role id", "type":"string", "description":"Id of the role", "jsonPath":".status.role_id"}"#,
+    namespaced
+)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpgoRoleSpec {
+    pub configuration: SftpgoRoleConfiguration,
+    /// Whether the resource in SFTPGo is replaced, or only the configured fields are changed.
+    /// Defaults to Full
+    pub field_management: Option<FieldManagement>,
+    #[serde(rename = "sftpgoServerReference")]
+    pub server_reference: ServerReference,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema, Default)]
+pub struct SftpgoRoleResourceStatus {
+    /// The name in SFTPGo, empty until the resource was created there
+    #[serde(default)]
+    last_name: String,
+    role_id: Option<i32>,
+    #[serde(flatten)]
+    pub request: RequestState,
+    #[serde(flatten)]
+    pub reconcile: ReconcileState,
+}
+
+impl SftpgoStatus for SftpgoRoleResourceStatus {
+    type Id = i32;
+
+    fn get_last_name(&self) -> &str {
+        &self.last_name
+    }
+
+    fn set_last_name(&mut self, name: &str) {
+        self.last_name = name.to_string();
+    }
+
+    fn get_id(&self) -> Option<Self::Id> {
+        self.role_id
+    }
+
+    fn set_id(&mut self, id: Option<Self::Id>) {
+        self.role_id = id;
+    }
+}
@@ -1,6 +1,9 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+/// Secret with the connection details of a SFTPGo server. The secret must contain the key "url",
+/// and either "username" and "password" of an admin, or "api-key" with an admin api key. If
+/// "api-key" is present it is used instead of the username and password.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct ConnectionSecret {
     pub name: String,
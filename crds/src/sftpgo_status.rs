@@ -1,9 +1,148 @@
-pub trait SftpgoStatus {
+use crate::{
+    SftpgoAdminResourceStatus, SftpgoApiKeyResourceStatus, SftpgoEventActionResourceStatus,
+    SftpgoEventRuleResourceStatus, SftpgoFolderResourceStatus, SftpgoGroupResourceStatus,
+    SftpgoIpListEntryResourceStatus, SftpgoRoleResourceStatus, SftpgoShareResourceStatus,
+    SftpgoUserResourceStatus,
+};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Condition;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// The outcome of the last reconciliation. Flattened into the status of every resource, so this
+// isn't a doc comment, as it would become the description of the statuses
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema, Default)]
+pub struct ReconcileState {
+    pub observed_generation: Option<i64>,
+    /// The error of the last failed reconciliation, cleared once it succeeds
+    pub last_error: Option<String>,
+    pub conditions: Option<Vec<Condition>>,
+}
+
+// What was last sent to SFTPGo. Flattened into the status of resources that are updated by
+// comparing them with SFTPGo
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema, Default)]
+pub struct RequestState {
+    /// Hash of the last request sent to SFTPGo, used to detect changes
+    pub request_hash: Option<String>,
+    /// Hashes of the top level fields of the last request, to tell fields changed in the
+    /// specification from fields changed in SFTPGo
+    pub field_hashes: Option<BTreeMap<String, String>>,
+}
+
+/// The outcome of the last reconciliation, reported by every resource
+pub trait ReconcileStatus {
+    fn reconcile_state(&self) -> &ReconcileState;
+    fn reconcile_state_mut(&mut self) -> &mut ReconcileState;
+
+    fn get_conditions(&self) -> Option<&Vec<Condition>> {
+        self.reconcile_state().conditions.as_ref()
+    }
+
+    fn conditions_mut(&mut self) -> &mut Option<Vec<Condition>> {
+        &mut self.reconcile_state_mut().conditions
+    }
+
+    fn get_observed_generation(&self) -> Option<i64> {
+        self.reconcile_state().observed_generation
+    }
+
+    fn set_observed_generation(&mut self, generation: Option<i64>) {
+        self.reconcile_state_mut().observed_generation = generation;
+    }
+
+    fn get_last_error(&self) -> Option<&str> {
+        self.reconcile_state().last_error.as_deref()
+    }
+
+    fn set_last_error(&mut self, error: Option<String>) {
+        self.reconcile_state_mut().last_error = error;
+    }
+}
+
+pub trait SftpgoStatus: ReconcileStatus {
+    /// The type of the id SFTPGo assigns to the resource
+    type Id;
+
     fn get_last_name(&self) -> &str;
     fn set_last_name(&mut self, name: &str);
 
-    fn get_id(&self) -> Option<i32>;
-    fn set_id(&mut self, id: Option<i32>);
+    fn get_id(&self) -> Option<Self::Id>;
+    fn set_id(&mut self, id: Option<Self::Id>);
+}
+
+pub trait RequestStatus {
+    fn request_state(&self) -> &RequestState;
+    fn request_state_mut(&mut self) -> &mut RequestState;
+
+    fn get_request_hash(&self) -> Option<&str> {
+        self.request_state().request_hash.as_deref()
+    }
+
+    fn set_request_hash(&mut self, hash: Option<String>) {
+        self.request_state_mut().request_hash = hash;
+    }
+
+    fn get_field_hashes(&self) -> Option<&BTreeMap<String, String>> {
+        self.request_state().field_hashes.as_ref()
+    }
+
+    fn set_field_hashes(&mut self, hashes: Option<BTreeMap<String, String>>) {
+        self.request_state_mut().field_hashes = hashes;
+    }
+}
+
+macro_rules! impl_reconcile_status {
+    ($($status:ty),*) => {
+        $(
+            impl ReconcileStatus for $status {
+                fn reconcile_state(&self) -> &ReconcileState {
+                    &self.reconcile
+                }
+
+                fn reconcile_state_mut(&mut self) -> &mut ReconcileState {
+                    &mut self.reconcile
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_request_status {
+    ($($status:ty),*) => {
+        $(
+            impl RequestStatus for $status {
+                fn request_state(&self) -> &RequestState {
+                    &self.request
+                }
+
+                fn request_state_mut(&mut self) -> &mut RequestState {
+                    &mut self.request
+                }
+            }
+        )*
+    };
+}
 
+impl_reconcile_status!(
+    SftpgoAdminResourceStatus,
+    SftpgoApiKeyResourceStatus,
+    SftpgoEventActionResourceStatus,
+    SftpgoEventRuleResourceStatus,
+    SftpgoFolderResourceStatus,
+    SftpgoGroupResourceStatus,
+    SftpgoIpListEntryResourceStatus,
+    SftpgoRoleResourceStatus,
+    SftpgoShareResourceStatus,
+    SftpgoUserResourceStatus
+);
 
-}
\ No newline at end of file
+impl_request_status!(
+    SftpgoAdminResourceStatus,
+    SftpgoEventActionResourceStatus,
+    SftpgoEventRuleResourceStatus,
+    SftpgoFolderResourceStatus,
+    SftpgoGroupResourceStatus,
+    SftpgoRoleResourceStatus,
+    SftpgoUserResourceStatus
+);
@@ -0,0 +1,75 @@
+use crate::{ReconcileState, UserReference, ValueFrom};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use kube::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub enum SftpgoShareScope {
+    /// Files can be downloaded from the share
+    #[default]
+    Read,
+    /// Files can be uploaded to the share. Only a single directory can be shared
+    Write,
+    /// Files can be both downloaded and uploaded. Only a single directory can be shared
+    ReadWrite,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpgoShareConfiguration {
+    /// The name of the share. Does not have to be unique.
+    pub name: String,
+    /// optional description
+    pub description: Option<String>,
+    pub scope: Option<SftpgoShareScope>,
+    /// The virtual paths to share, relative to the home directory of the user
+    pub paths: Vec<String>,
+    /// When the share expires. If not set the share never expires
+    pub expires_at: Option<Time>,
+    /// The maximum number of times the share can be used. If not set there is no limit
+    pub max_tokens: Option<i32>,
+    /// The password protecting the share, usually from a secret. If not set the share is
+    /// accessible to anyone with the link
+    pub password_from: Option<ValueFrom>,
+    /// IP addresses or networks allowed to use the share, in CIDR notation, for example
+    /// "192.168.1.0/24"
+    pub allow_from: Option<Vec<String>>,
+}
+
+#[derive(CustomResource, Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema)]
+#[kube(
+    group = "sftpgo.digizuite.com",
+    version = "v1alpha1",
+    kind = "SftpgoShare",
+    plural = "sftpgoshares",
+    derive = "PartialEq",
+    status = "SftpgoShareResourceStatus",
+    printcolumn = r#"{"name":"Ready", "type":"string", "description":"Whether the resource is reconciled", "jsonPath":".status.conditions[?(@.type==\"Ready\")].status"}"#,
+    printcolumn = r#"{"name":"Share id", "type":"string", "description":"Id of the share", "jsonPath":".status.share_id"}"#,
+    printcolumn = r#"{"name":"Url", "type":"string", "description":"Link to the share", "jsonPath":".status.url"}"#,
+    namespaced
+)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpgoShareSpec {
+    pub configuration: SftpgoShareConfiguration,
+    /// The SftpgoUser owning the share. The share is created on the server of the user, using
+    /// the password of the user.
+    pub user_reference: UserReference,
+    /// The base url the share link is published under, for example "https://files.example.com".
+    /// Defaults to the url used to connect to the server.
+    pub public_url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema, Default)]
+pub struct SftpgoShareResourceStatus {
+    pub share_id: Option<String>,
+    /// The username of the user owning the share
+    pub username: Option<String>,
+    /// The link to give to the recipients of the share
+    pub url: Option<String>,
+    /// Hash of the password last sent to SFTPGo, used to detect changes to it
+    pub password_hash: Option<String>,
+    #[serde(flatten)]
+    pub reconcile: ReconcileState,
+}
@@ -1,9 +1,16 @@
+use crate::group_reference::GroupReference;
+use crate::role_reference::RoleReference;
 use crate::sftpgo_server_reference::ServerReference;
 use crate::virtual_folder_reference::VirtualFolderReference;
-use crate::{FileSystem, SftpgoStatus};
+use crate::{
+    FieldManagement, FileSystem, ProvidedValue, ReconcileState, RequestState, SecretKeyReference,
+    SftpgoStatus,
+};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
 use kube::CustomResource;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub enum SftpgoUserStatus {
@@ -33,26 +40,28 @@ pub enum UserPermission {
     Chtimes,
 }
 
-impl ToString for UserPermission {
-    fn to_string(&self) -> String {
-        match self {
-            UserPermission::All => "*".to_string(),
-            UserPermission::List => "list".to_string(),
-            UserPermission::Download => "download".to_string(),
-            UserPermission::Upload => "upload".to_string(),
-            UserPermission::Overwrite => "overwrite".to_string(),
-            UserPermission::CreateDirs => "create_dirs".to_string(),
-            UserPermission::Rename => "rename".to_string(),
-            UserPermission::RenameFiles => "rename_files".to_string(),
-            UserPermission::RenameDirs => "rename_dirs".to_string(),
-            UserPermission::Delete => "delete".to_string(),
-            UserPermission::DeleteFiles => "delete_files".to_string(),
-            UserPermission::DeleteDirs => "delete_dirs".to_string(),
-            UserPermission::CreateSymlinks => "create_symlinks".to_string(),
-            UserPermission::Chmod => "chmod".to_string(),
-            UserPermission::Chown => "chown".to_string(),
-            UserPermission::Chtimes => "chtimes".to_string(),
-        }
+impl Display for UserPermission {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            UserPermission::All => "*",
+            UserPermission::List => "list",
+            UserPermission::Download => "download",
+            UserPermission::Upload => "upload",
+            UserPermission::Overwrite => "overwrite",
+            UserPermission::CreateDirs => "create_dirs",
+            UserPermission::Rename => "rename",
+            UserPermission::RenameFiles => "rename_files",
+            UserPermission::RenameDirs => "rename_dirs",
+            UserPermission::Delete => "delete",
+            UserPermission::DeleteFiles => "delete_files",
+            UserPermission::DeleteDirs => "delete_dirs",
+            UserPermission::CreateSymlinks => "create_symlinks",
+            UserPermission::Chmod => "chmod",
+            UserPermission::Chown => "chown",
+            UserPermission::Chtimes => "chtimes",
+        };
+
+        write!(f, "{}", value)
     }
 }
 
@@ -62,20 +71,178 @@ pub struct DirectoryPermission {
     pub permissions: Vec<UserPermission>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum LoginMethod {
+    PublicKey,
+    Password,
+    /// Password authentication over SSH only, other protocols are still allowed
+    PasswordOverSsh,
+    KeyboardInteractive,
+    PublicKeyAndPassword,
+    PublicKeyAndKeyboardInteractive,
+    TlsCertificate,
+    TlsCertificateAndPassword,
+}
+
+impl Display for LoginMethod {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            LoginMethod::PublicKey => "publickey",
+            LoginMethod::Password => "password",
+            LoginMethod::PasswordOverSsh => "password-over-SSH",
+            LoginMethod::KeyboardInteractive => "keyboard-interactive",
+            LoginMethod::PublicKeyAndPassword => "publickey+password",
+            LoginMethod::PublicKeyAndKeyboardInteractive => "publickey+keyboard-interactive",
+            LoginMethod::TlsCertificate => "TLSCertificate",
+            LoginMethod::TlsCertificateAndPassword => "TLSCertificate+password",
+        };
+
+        write!(f, "{}", value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub enum SftpgoProtocol {
+    Ssh,
+    Ftp,
+    WebDav,
+    Http,
+}
+
+impl Display for SftpgoProtocol {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            SftpgoProtocol::Ssh => "SSH",
+            SftpgoProtocol::Ftp => "FTP",
+            SftpgoProtocol::WebDav => "DAV",
+            SftpgoProtocol::Http => "HTTP",
+        };
+
+        write!(f, "{}", value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub enum TlsUsername {
+    None,
+    CommonName,
+}
+
+impl Display for TlsUsername {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            TlsUsername::None => "None",
+            TlsUsername::CommonName => "CommonName",
+        };
+
+        write!(f, "{}", value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub enum DenyPolicy {
+    Default,
+    /// Denied files are hidden from directory listings
+    Hide,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FilePatterns {
+    /// Virtual path the patterns apply to, including sub directories
+    pub path: String,
+    /// Shell like patterns, for example "*.jpg". If set, only matching files are allowed
+    pub allowed_patterns: Option<Vec<String>>,
+    /// Shell like patterns, for example "*.exe". Denied patterns take precedence
+    pub denied_patterns: Option<Vec<String>>,
+    pub deny_policy: Option<DenyPolicy>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BandwidthLimit {
+    /// IP addresses or networks in CIDR notation the limits apply to
+    pub sources: Vec<String>,
+    /// Maximum upload bandwidth as KB/s. 0 means unlimited
+    pub upload_bandwidth: Option<i64>,
+    /// Maximum download bandwidth as KB/s. 0 means unlimited
+    pub download_bandwidth: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpgoUserFilters {
+    /// IP addresses or networks in CIDR notation the user can login from. Empty means any
+    pub allowed_ip: Option<Vec<String>>,
+    /// IP addresses or networks in CIDR notation the user can't login from
+    pub denied_ip: Option<Vec<String>>,
+    /// Login methods the user can't use. Deny password and keyboardInteractive for key-only
+    /// users
+    pub denied_login_methods: Option<Vec<LoginMethod>>,
+    pub denied_protocols: Option<Vec<SftpgoProtocol>>,
+    pub file_patterns: Option<Vec<FilePatterns>>,
+    /// Maximum size of a single uploaded file in bytes. 0 means unlimited
+    pub max_upload_file_size: Option<i64>,
+    /// How the username is read from TLS certificates
+    pub tls_username: Option<TlsUsername>,
+    /// Directory the user starts in after login, as a virtual path
+    pub start_directory: Option<String>,
+    /// Protocols that require two-factor authentication. WebDav is not supported
+    pub two_factor_protocols: Option<Vec<SftpgoProtocol>>,
+    /// Bandwidth limits depending on the source network. Takes precedence over the user limits
+    pub bandwidth_limits: Option<Vec<BandwidthLimit>>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SftpgoUserConfiguration {
     /// The username of the user
     pub username: String,
-    /// Password of the user. Changes to this field will not propagate to the user after creation as we have
-    /// no way of retrieving the password from the server.
-    pub password: String,
+    /// Password of the user. Prefer passwordFrom, so the password isn't stored in the resource.
+    /// At most one of password, passwordFrom and generatePassword can be set. Users without a
+    /// password need public keys. Removing the password doesn't remove it from SFTPGo, deny
+    /// the password login methods instead.
+    pub password: Option<String>,
+    /// Secret containing the password of the user. The user is updated when the content of the
+    /// secret changes, so the password can be rotated.
+    pub password_from: Option<SecretKeyReference>,
+    /// Let the operator generate the password. It is published together with the connection
+    /// details in a servicebinding.io secret, so workloads can bind to the user directly.
+    pub generate_password: Option<bool>,
+    /// SSH public keys of the user. A value read from a secret or config map can contain
+    /// multiple keys, one per line like an authorized_keys file.
+    pub public_keys: Option<Vec<ProvidedValue<String>>>,
     pub enabled: Option<SftpgoUserStatus>,
     pub global_permissions: Vec<UserPermission>,
     pub per_directory_permissions: Option<Vec<DirectoryPermission>>,
     pub filesystem: Option<FileSystem>,
     pub home_dir: String,
     pub virtual_folders: Option<Vec<VirtualFolderReference>>,
+    /// The groups the user is a member of. At most one group can be the primary group.
+    pub groups: Option<Vec<GroupReference>>,
+    /// The role of the user. Admins with a role can only administer users with the same role.
+    pub role_reference: Option<RoleReference>,
+    /// When the account expires. Expired users can't login. If not set the account never expires
+    pub expires_at: Option<Time>,
+    /// Maximum concurrent sessions. 0 means unlimited
+    pub max_sessions: Option<i32>,
+    /// Quota as size in bytes. 0 means unlimited
+    pub quota_size: Option<i64>,
+    /// Quota as number of files. 0 means unlimited
+    pub quota_files: Option<i32>,
+    /// Maximum upload bandwidth as KB/s. 0 means unlimited
+    pub upload_bandwidth: Option<i64>,
+    /// Maximum download bandwidth as KB/s. 0 means unlimited
+    pub download_bandwidth: Option<i64>,
+    /// Maximum data transfer allowed for uploads as MB. 0 means unlimited
+    pub upload_data_transfer: Option<i64>,
+    /// Maximum data transfer allowed for downloads as MB. 0 means unlimited
+    pub download_data_transfer: Option<i64>,
+    /// Maximum total data transfer as MB. Takes precedence over the upload and download limits.
+    /// 0 means unlimited
+    pub total_data_transfer: Option<i64>,
+    pub filters: Option<SftpgoUserFilters>,
 }
 
 #[derive(CustomResource, Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema)]
@@ -86,25 +253,91 @@ pub struct SftpgoUserConfiguration {
     plural = "sftpgousers",
     derive = "PartialEq",
     status = "SftpgoUserResourceStatus",
+    printcolumn = r#"{"name":"Ready", "type":"string", "description":"Whether the resource is reconciled", "jsonPath":".status.conditions[?(@.type==\"Ready\")].status"}"#,
     printcolumn = r#"{"name":"User id", "type":"string", "description":"Id of the user", "jsonPath":".status.user_id"}"#,
+    printcolumn = r#"{"name":"Used size", "type":"integer", "description":"Used quota in bytes", "jsonPath":".status.usage.used_quota_size"}"#,
+    printcolumn = r#"{"name":"Used files", "type":"integer", "description":"Used quota in number of files", "jsonPath":".status.usage.used_quota_files"}"#,
+    printcolumn = r#"{"name":"Last login", "type":"date", "description":"When the user last logged in", "jsonPath":".status.usage.last_login"}"#,
+    printcolumn = r#"{"name":"Expired", "type":"boolean", "description":"Whether the account has expired", "jsonPath":".status.expired"}"#,
     namespaced
 )]
 #[serde(rename_all = "camelCase")]
 pub struct SftpgoUserSpec {
     pub configuration: SftpgoUserConfiguration,
-    /// This way you force the user to login again, if connected, and so to use the new configuration
+    /// Close the active sessions of the user when it is changed. This way you force the user to
+    /// login again, if connected, and so to use the new configuration
     pub disconnect_on_change: Option<bool>,
+    /// Name of the secret the generated credentials are written to. Defaults to `<name>-binding`
+    pub binding_secret_name: Option<String>,
+    /// What to do once the account has expired. By default the user is left as is
+    pub on_expiry: Option<ExpiryPolicy>,
+    /// Percentage of the quota size or files at which the QuotaThresholdExceeded condition is
+    /// set, for example 90
+    pub quota_warning_threshold: Option<i32>,
+    /// Allow changing the passphrase of an encrypted filesystem, or turning encryption on or
+    /// off, while the resource has files in SFTPGo. Files stored before can then no longer be read
+    pub allow_passphrase_change: Option<bool>,
+    /// Whether the resource in SFTPGo is replaced, or only the configured fields are changed.
+    /// Defaults to Full
+    pub field_management: Option<FieldManagement>,
     #[serde(rename = "sftpgoServerReference")]
     pub server_reference: ServerReference,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub enum ExpiryPolicy {
+    /// Disable the user in SFTPGo
+    Disable,
+    /// Delete this resource, which removes the user from SFTPGo
+    Delete,
+}
+
+/// Reference to the secret with the generated credentials, as defined for provisioned services
+/// by the servicebinding.io spec
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema)]
+pub struct SftpgoUserBinding {
+    pub name: String,
+}
+
+/// Usage of the user as reported by SFTPGo
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema, Default)]
+pub struct SftpgoUserUsage {
+    /// bytes
+    pub used_quota_size: i64,
+    pub used_quota_files: i32,
+    /// bytes
+    pub used_upload_data_transfer: i64,
+    /// bytes
+    pub used_download_data_transfer: i64,
+    pub last_login: Option<Time>,
+    pub first_upload: Option<Time>,
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema, Default)]
 pub struct SftpgoUserResourceStatus {
+    /// The name in SFTPGo, empty until the resource was created there
+    #[serde(default)]
     pub last_username: String,
     pub user_id: Option<i32>,
+    /// Hash of the passphrase of the encrypted filesystem, used to detect changes to it
+    pub passphrase_hash: Option<String>,
+    /// Hash of the password last sent to SFTPGo, used to detect changes to it
+    pub password_hash: Option<String>,
+    pub binding: Option<SftpgoUserBinding>,
+    #[serde(flatten)]
+    pub request: RequestState,
+    #[serde(default)]
+    pub expired: bool,
+    /// Time left until the account expires, as of the last reconciliation
+    pub remaining_time: Option<String>,
+    pub usage: Option<SftpgoUserUsage>,
+    #[serde(flatten)]
+    pub reconcile: ReconcileState,
 }
 
 impl SftpgoStatus for SftpgoUserResourceStatus {
+    type Id = i32;
+
     fn get_last_name(&self) -> &str {
         &self.last_username
     }
@@ -113,11 +346,11 @@ impl SftpgoStatus for SftpgoUserResourceStatus {
         self.last_username = name.to_string();
     }
 
-    fn get_id(&self) -> Option<i32> {
+    fn get_id(&self) -> Option<Self::Id> {
         self.user_id
     }
 
-    fn set_id(&mut self, id: Option<i32>) {
+    fn set_id(&mut self, id: Option<Self::Id>) {
         self.user_id = id;
     }
 }
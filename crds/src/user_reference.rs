@@ -0,0 +1,12 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserReference {
+    /// The kubernetes resource name of the user
+    pub name: String,
+    /// The kubernetes namespace the user is defined in, if different from the namespace
+    /// of this resource.
+    pub namespace: Option<String>,
+}
@@ -1,10 +1,10 @@
 use crate::reconciler::{ContextData, SftpgoResource};
-use crate::user_reconciler::MapEnabled;
+use crate::role_reconciler::get_role_name;
+use crate::user_reconciler::get_enabled_status;
 use crate::Error;
 use async_trait::async_trait;
-use crds::{ServerReference, SftpgoAdmin};
+use crds::{FieldManagement, ServerReference, SftpgoAdmin};
 use sftpgo_client::admins::{AdminRequest, AdminResponse};
-use sftpgo_client::UserStatus;
 
 #[async_trait]
 impl SftpgoResource for SftpgoAdmin {
@@ -17,11 +17,39 @@ impl SftpgoResource for SftpgoAdmin {
 
     async fn get_request(
         &self,
-        _context: &ContextData,
-        _namespace: &String,
+        context: &ContextData,
+        namespace: &str,
     ) -> Result<Self::Request, Error> {
         let admin_conf = &self.spec.configuration;
 
+        let role = match (&admin_conf.role, &admin_conf.role_reference) {
+            (Some(_), Some(_)) => {
+                return Err(Error::UserInput(
+                    "Both role and roleReference are set. Only one can be specified".to_string(),
+                ))
+            }
+            (Some(role), None) => Some(role.clone()),
+            (None, Some(reference)) => Some(get_role_name(namespace, reference, context).await?),
+            (None, None) => None,
+        };
+
+        if role.is_some() {
+            let forbidden: Vec<String> = admin_conf
+                .permissions
+                .iter()
+                .filter(|p| !p.is_allowed_for_role_admin())
+                .map(|p| p.to_string())
+                .collect();
+
+            if !forbidden.is_empty() {
+                return Err(Error::UserInput(format!(
+                    "Admin {} has a role and cannot have the following permissions: {}",
+                    admin_conf.username,
+                    forbidden.join(", ")
+                )));
+            }
+        }
+
         let request = AdminRequest {
             username: admin_conf.username.clone(),
             description: admin_conf.description.clone(),
@@ -32,10 +60,8 @@ impl SftpgoResource for SftpgoAdmin {
                 .iter()
                 .map(|p| p.to_string())
                 .collect(),
-            status: admin_conf
-                .enabled
-                .map_or(UserStatus::Enabled, |status| status.map_enabled()),
-            role: admin_conf.role.clone(),
+            status: get_enabled_status(admin_conf.enabled.as_ref(), self.get_field_management()),
+            role,
         };
 
         Ok(request)
@@ -44,4 +70,8 @@ impl SftpgoResource for SftpgoAdmin {
     fn get_server_reference(&self) -> &ServerReference {
         &self.spec.server_reference
     }
+
+    fn get_field_management(&self) -> FieldManagement {
+        self.spec.field_management.unwrap_or_default()
+    }
 }
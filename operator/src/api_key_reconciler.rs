@@ -0,0 +1,398 @@
+use crate::conditions::set_reconcile_succeeded;
+use crate::consts::{ANNOTATION_API_KEY_ID, SECRET_KEY_API_KEY};
+use crate::default;
+use crate::finalizers::{ensure_finalizer, remove_finalizer};
+use crate::reconciler::{ContextData, Error};
+use crate::sftpgo_multi_client::{get_authorized_client, OperatorSftpgoClient};
+use crds::{
+    ApiKeyPrincipalReference, SftpgoAdmin, SftpgoApiKey, SftpgoApiKeyScope, SftpgoStatus,
+    SftpgoUser,
+};
+use k8s_openapi::api::core::v1::Secret;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::api::Patch;
+use kube::core::object::HasStatus;
+use kube::runtime::controller::Action;
+use kube::{Api, Resource, ResourceExt};
+use serde::de::DeserializeOwned;
+use sftpgo_client::api_keys::{
+    ApiKeyClient, ApiKeyRequest, ApiKeyResponse, ApiKeyScope, CreatedApiKey,
+};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+pub async fn reconcile_sftpgo_api_key(
+    resource: Arc<SftpgoApiKey>,
+    context: Arc<ContextData>,
+) -> Result<Action, Error> {
+    let name = resource.name_any();
+
+    let namespace = resource.namespace().ok_or(Error::UserInput(
+        "Expected SftpgoApiKey resource to be namespaced. Can't deploy to unknown namespace."
+            .to_string(),
+    ))?;
+
+    let resource_api: Api<SftpgoApiKey> =
+        Api::namespaced(context.kubernetes_client.clone(), &namespace);
+
+    let mut resource = resource_api.get(&name).await?;
+
+    let api_client =
+        get_authorized_client(&resource.spec.server_reference, &context, &namespace).await?;
+
+    let secret_name = resource
+        .spec
+        .secret_name
+        .clone()
+        .unwrap_or_else(|| format!("{}-api-key", name));
+
+    let secret_api: Api<Secret> = Api::namespaced(context.kubernetes_client.clone(), &namespace);
+    let existing_secret = secret_api.get_opt(&secret_name).await?;
+
+    let status_key_id = resource.status().and_then(|s| s.get_id());
+    let secret_key_id = existing_secret.as_ref().and_then(get_secret_key_id);
+
+    if resource.meta().deletion_timestamp.is_some() {
+        info!("Api key {namespace}/{name} is being deleted, cleaning up");
+
+        // Both, in case the status couldn't be updated after the last key was created
+        let mut key_ids: Vec<&String> = status_key_id.iter().chain(&secret_key_id).collect();
+        key_ids.dedup();
+
+        for key_id in key_ids {
+            api_client.delete_api_key(key_id).await?;
+            info!("Deleted api key {} from SFTPGo", key_id);
+        }
+
+        remove_finalizer::<SftpgoApiKey>(context.kubernetes_client.clone(), &name, &namespace)
+            .await?;
+
+        info!("Removed finalizer");
+
+        return Ok(Action::await_change());
+    }
+
+    resource = ensure_finalizer(resource, context.kubernetes_client.clone()).await?;
+
+    let request = get_request(&resource, &context, &namespace).await?;
+
+    let previous_secret_name = resource.status().and_then(|s| s.secret_name.clone());
+    if let Some(previous_secret_name) = previous_secret_name.filter(|n| *n != secret_name) {
+        delete_previous_secret(
+            &resource,
+            &secret_api,
+            &previous_secret_name,
+            &api_client,
+            secret_key_id.as_ref().or(status_key_id.as_ref()),
+        )
+        .await?;
+    }
+
+    // The secret is written before the status, so if they disagree the status couldn't be
+    // updated after a new key was created, and the key in the status is left over
+    if let (Some(status_key_id), Some(secret_key_id)) = (&status_key_id, &secret_key_id) {
+        if status_key_id != secret_key_id {
+            info!(
+                "Deleting api key {} replaced by {}",
+                status_key_id, secret_key_id
+            );
+            api_client.delete_api_key(status_key_id).await?;
+        }
+    }
+
+    let key_id = secret_key_id.or(status_key_id);
+
+    let existing_key = if let Some(key_id) = &key_id {
+        api_client.get_api_key(key_id).await?
+    } else {
+        None
+    };
+
+    match (existing_key, existing_secret) {
+        (Some(existing_key), Some(_)) => {
+            if key_matches_request(&existing_key, &request) {
+                debug!("Api key {} is in sync with SFTPGo", existing_key.id);
+            } else {
+                info!("Updating api key {}", existing_key.id);
+
+                api_client
+                    .update_api_key(&existing_key.id, &request)
+                    .await?;
+
+                info!("Updated api key {}", existing_key.id);
+            }
+
+            let mut s = resource.status.clone().unwrap_or_default();
+            s.set_last_name(&request.name);
+            s.set_id(Some(existing_key.id));
+            s.secret_name = Some(secret_name);
+            set_reconcile_succeeded(&mut s, resource.metadata.generation);
+
+            if resource.status.as_ref() != Some(&s) {
+                resource.status = Some(s);
+
+                resource_api
+                    .patch_status(&name, &default(), &Patch::Merge(resource))
+                    .await?;
+
+                info!("Updated status for api key {namespace}/{name}");
+            }
+
+            return Ok(Action::await_change());
+        }
+        (Some(existing_key), None) => {
+            info!(
+                "Secret {} for api key {} is missing, generating a new key",
+                secret_name, existing_key.id
+            );
+
+            api_client.delete_api_key(&existing_key.id).await?;
+        }
+        (None, _) => {
+            info!("Creating api key for {namespace}/{name}");
+        }
+    }
+
+    let created = api_client.create_api_key(&request).await?;
+
+    info!("Created api key {}", created.id);
+
+    if let Err(e) = write_secret(&resource, &secret_api, &secret_name, &created).await {
+        // Without the secret nobody can use the key, so don't leave it behind
+        api_client.delete_api_key(&created.id).await?;
+        return Err(e);
+    }
+
+    let generation = resource.metadata.generation;
+    let status = resource.status_mut();
+
+    let mut s = status.clone().unwrap_or_default();
+    s.set_last_name(&request.name);
+    s.set_id(Some(created.id));
+    s.secret_name = Some(secret_name);
+    set_reconcile_succeeded(&mut s, generation);
+
+    *status = Some(s);
+
+    resource_api
+        .patch_status(&name, &default(), &Patch::Merge(resource))
+        .await?;
+
+    info!("Updated status for api key {namespace}/{name}");
+
+    Ok(Action::await_change())
+}
+
+async fn write_secret(
+    resource: &SftpgoApiKey,
+    secret_api: &Api<Secret>,
+    secret_name: &str,
+    created: &CreatedApiKey,
+) -> Result<(), Error> {
+    let mut secret_data: BTreeMap<String, String> = BTreeMap::new();
+    secret_data.insert(SECRET_KEY_API_KEY.to_string(), created.key.clone());
+
+    let secret = Secret {
+        metadata: ObjectMeta {
+            name: Some(secret_name.to_string()),
+            annotations: Some(BTreeMap::from([(
+                ANNOTATION_API_KEY_ID.to_string(),
+                created.id.clone(),
+            )])),
+            // Always comes from the api, so according to the docs it's safe to unwrap
+            owner_references: Some(vec![resource.controller_owner_ref(&()).unwrap()]),
+            ..default()
+        },
+        string_data: Some(secret_data),
+        ..default()
+    };
+
+    if secret_api.get_opt(secret_name).await?.is_some() {
+        debug!("Replacing secret {}", secret_name);
+        secret_api.replace(secret_name, &default(), &secret).await?;
+    } else {
+        debug!("Creating secret {}", secret_name);
+        secret_api.create(&default(), &secret).await?;
+    }
+
+    Ok(())
+}
+
+/// Deletes the secret the key was written to before `secretName` changed, and the key in it,
+/// unless it's the current key. Secrets not created for this resource are left alone.
+async fn delete_previous_secret(
+    resource: &SftpgoApiKey,
+    secret_api: &Api<Secret>,
+    previous_secret_name: &str,
+    api_client: &OperatorSftpgoClient,
+    current_key_id: Option<&String>,
+) -> Result<(), Error> {
+    let Some(previous_secret) = secret_api.get_opt(previous_secret_name).await? else {
+        return Ok(());
+    };
+
+    let owned = previous_secret
+        .owner_references()
+        .iter()
+        .any(|o| Some(&o.uid) == resource.uid().as_ref());
+
+    if !owned {
+        return Ok(());
+    }
+
+    if let Some(key_id) =
+        get_secret_key_id(&previous_secret).filter(|id| Some(id) != current_key_id)
+    {
+        api_client.delete_api_key(&key_id).await?;
+        info!("Deleted api key {} from SFTPGo", key_id);
+    }
+
+    secret_api.delete(previous_secret_name, &default()).await?;
+
+    info!("Deleted previous api key secret {}", previous_secret_name);
+
+    Ok(())
+}
+
+/// Whether the key in SFTPGo already is as requested, so it doesn't have to be updated
+fn key_matches_request(existing: &ApiKeyResponse, request: &ApiKeyRequest) -> bool {
+    existing.name == request.name
+        && existing.scope == request.scope
+        && existing.expires_at.unwrap_or_default() == request.expires_at.unwrap_or_default()
+        && existing.description.as_deref().unwrap_or_default()
+            == request.description.as_deref().unwrap_or_default()
+        && existing.user == request.user
+        && existing.admin == request.admin
+}
+
+fn get_secret_key_id(secret: &Secret) -> Option<String> {
+    secret.annotations().get(ANNOTATION_API_KEY_ID).cloned()
+}
+
+async fn get_request(
+    resource: &SftpgoApiKey,
+    context: &ContextData,
+    namespace: &str,
+) -> Result<ApiKeyRequest, Error> {
+    let configuration = &resource.spec.configuration;
+
+    let (scope, admin, user) = match configuration.scope {
+        SftpgoApiKeyScope::Admin => {
+            if configuration.user_reference.is_some() {
+                return Err(Error::UserInput(
+                    "userReference can only be set for api keys with the User scope".to_string(),
+                ));
+            }
+
+            let admin = if let Some(reference) = &configuration.admin_reference {
+                Some(get_principal_name::<SftpgoAdmin>(namespace, reference, context).await?)
+            } else {
+                None
+            };
+
+            (ApiKeyScope::Admin, admin, None)
+        }
+        SftpgoApiKeyScope::User => {
+            if configuration.admin_reference.is_some() {
+                return Err(Error::UserInput(
+                    "adminReference can only be set for api keys with the Admin scope".to_string(),
+                ));
+            }
+
+            let user = if let Some(reference) = &configuration.user_reference {
+                Some(get_principal_name::<SftpgoUser>(namespace, reference, context).await?)
+            } else {
+                None
+            };
+
+            (ApiKeyScope::User, None, user)
+        }
+    };
+
+    Ok(ApiKeyRequest {
+        name: configuration.name.clone(),
+        scope,
+        expires_at: configuration
+            .expires_at
+            .as_ref()
+            .map(|t| t.0.timestamp_millis()),
+        description: configuration.description.clone(),
+        user,
+        admin,
+    })
+}
+
+async fn get_principal_name<TResource>(
+    namespace: &str,
+    crd_ref: &ApiKeyPrincipalReference,
+    context: &ContextData,
+) -> Result<String, Error>
+where
+    TResource: Resource<Scope = k8s_openapi::NamespaceResourceScope>
+        + HasStatus
+        + Clone
+        + DeserializeOwned
+        + Debug,
+    <TResource as Resource>::DynamicType: Default,
+    <TResource as HasStatus>::Status: SftpgoStatus<Id = i32>,
+{
+    let ns = crd_ref.namespace.as_deref().unwrap_or(namespace);
+
+    let api: Api<TResource> = Api::namespaced(context.kubernetes_client.clone(), ns);
+
+    if let Some(principal) = api.get_opt(&crd_ref.name).await? {
+        if let Some(status) = principal.status() {
+            if status.get_id().is_none() {
+                return Err(Error::NotReady(crd_ref.name.to_string()));
+            }
+
+            Ok(status.get_last_name().to_string())
+        } else {
+            Err(Error::NotReady(crd_ref.name.to_string()))
+        }
+    } else {
+        Err(Error::UserInput(format!(
+            "{} {} not found in namespace {}",
+            TResource::kind(&default()),
+            crd_ref.name,
+            ns
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unset_values_match_empty_values_from_sftpgo() {
+        let request = ApiKeyRequest {
+            name: "ci".to_string(),
+            scope: ApiKeyScope::User,
+            expires_at: None,
+            description: None,
+            user: Some("test".to_string()),
+            admin: None,
+        };
+        let existing = ApiKeyResponse {
+            id: "key-id".to_string(),
+            name: "ci".to_string(),
+            scope: ApiKeyScope::User,
+            expires_at: Some(0),
+            last_use_at: Some(1700000000000),
+            description: Some(String::new()),
+            user: Some("test".to_string()),
+            admin: None,
+        };
+
+        assert!(key_matches_request(&existing, &request));
+        assert!(!key_matches_request(
+            &existing,
+            &ApiKeyRequest {
+                description: Some("changed".to_string()),
+                ..request
+            }
+        ));
+    }
+}
@@ -0,0 +1,156 @@
+use crate::reconciler::Error;
+use crds::ReconcileStatus;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{Condition, Time};
+use k8s_openapi::chrono::Utc;
+
+/// The resource is fully reconciled
+pub const CONDITION_READY: &str = "Ready";
+/// The resource in SFTPGo matches the specification
+pub const CONDITION_SYNCED: &str = "Synced";
+/// All resources this resource references exist and are ready
+pub const CONDITION_DEPENDENCIES_READY: &str = "DependenciesReady";
+/// The resource in SFTPGo was changed outside of the operator, and set back on the last check
+pub const CONDITION_DRIFTED: &str = "Drifted";
+
+/// Why a reconciliation failed, in a form that can be recorded in the status after the error
+/// itself is gone
+#[derive(Debug, Clone)]
+pub struct ReconcileFailure {
+    pub reason: &'static str,
+    pub message: String,
+    pub dependencies_not_ready: bool,
+}
+
+impl From<&Error> for ReconcileFailure {
+    fn from(error: &Error) -> Self {
+        let reason = match error {
+            Error::Kube { .. } | Error::KubeWatcher { .. } => "KubernetesError",
+            Error::ViperSerializer { .. } | Error::UserInput(_) | Error::DecodeError(_) => {
+                "InvalidConfiguration"
+            }
+            Error::NotReady(_) => "DependencyNotReady",
+            Error::SftpgoRequestFailed(_) => "SftpgoRequestFailed",
+            Error::SerializationFailed(_) => "SerializationFailed",
+        };
+
+        ReconcileFailure {
+            reason,
+            message: error.to_string(),
+            dependencies_not_ready: matches!(error, Error::NotReady(_)),
+        }
+    }
+}
+
+/// Marks the resource as ready and in sync with SFTPGo
+pub fn set_reconcile_succeeded<S: ReconcileStatus>(status: &mut S, generation: Option<i64>) {
+    let conditions = status.conditions_mut();
+
+    set_condition(
+        conditions,
+        CONDITION_DEPENDENCIES_READY,
+        true,
+        "DependenciesReady",
+        String::new(),
+        generation,
+    );
+    set_condition(
+        conditions,
+        CONDITION_SYNCED,
+        true,
+        "Synced",
+        String::new(),
+        generation,
+    );
+    set_condition(
+        conditions,
+        CONDITION_READY,
+        true,
+        "Reconciled",
+        String::new(),
+        generation,
+    );
+
+    status.set_observed_generation(generation);
+    status.set_last_error(None);
+}
+
+/// Marks the resource as not ready. The dependencies are only marked as not ready if that is
+/// what the reconciliation failed on.
+pub fn set_reconcile_failed<S: ReconcileStatus>(
+    status: &mut S,
+    failure: &ReconcileFailure,
+    generation: Option<i64>,
+) {
+    let conditions = status.conditions_mut();
+
+    if failure.dependencies_not_ready {
+        set_condition(
+            conditions,
+            CONDITION_DEPENDENCIES_READY,
+            false,
+            failure.reason,
+            failure.message.clone(),
+            generation,
+        );
+    }
+
+    set_condition(
+        conditions,
+        CONDITION_SYNCED,
+        false,
+        failure.reason,
+        failure.message.clone(),
+        generation,
+    );
+    set_condition(
+        conditions,
+        CONDITION_READY,
+        false,
+        failure.reason,
+        failure.message.clone(),
+        generation,
+    );
+
+    status.set_observed_generation(generation);
+    status.set_last_error(Some(failure.message.clone()));
+}
+
+/// Sets the condition of the given type. The transition time is only updated when the status
+/// of the condition changes.
+pub fn set_condition(
+    conditions: &mut Option<Vec<Condition>>,
+    type_: &str,
+    status: bool,
+    reason: &str,
+    message: String,
+    observed_generation: Option<i64>,
+) {
+    let status = if status { "True" } else { "False" }.to_string();
+    let conditions = conditions.get_or_insert_with(Vec::new);
+
+    if let Some(existing) = conditions.iter_mut().find(|c| c.type_ == type_) {
+        if existing.status != status {
+            existing.last_transition_time = Time(Utc::now());
+        }
+
+        existing.status = status;
+        existing.reason = reason.to_string();
+        existing.message = message;
+        existing.observed_generation = observed_generation;
+    } else {
+        conditions.push(Condition {
+            type_: type_.to_string(),
+            status,
+            reason: reason.to_string(),
+            message,
+            observed_generation,
+            last_transition_time: Time(Utc::now()),
+        });
+    }
+}
+
+pub fn remove_condition(conditions: &mut Option<Vec<Condition>>, type_: &str) {
+    if let Some(c) = conditions {
+        c.retain(|c| c.type_ != type_);
+    }
+}
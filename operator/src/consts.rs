@@ -1,3 +1,12 @@
 pub const SECRET_KEY_USERNAME: &str = "username";
 pub const SECRET_KEY_PASSWORD: &str = "password";
 pub const SECRET_KEY_URL: &str = "url";
+pub const SECRET_KEY_API_KEY: &str = "api-key";
+pub const SECRET_KEY_TYPE: &str = "type";
+pub const SECRET_KEY_PROVIDER: &str = "provider";
+pub const SECRET_KEY_HOST: &str = "host";
+pub const SECRET_KEY_PORT: &str = "port";
+
+/// Records which api key an api key secret holds, so the key can be found even if the status
+/// couldn't be updated after it was created
+pub const ANNOTATION_API_KEY_ID: &str = "sftpgo.digizuite.com/api-key-id";
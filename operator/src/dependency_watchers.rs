@@ -0,0 +1,469 @@
+use crate::default;
+use crate::filesystem::get_value_sources as get_file_system_value_sources;
+use crds::{
+    ProvidedValue, RoleReference, SftpgoAdmin, SftpgoEventAction, SftpgoEventActionKind,
+    SftpgoEventRule, SftpgoFolder, SftpgoGroup, SftpgoRole, SftpgoShare, SftpgoUser, ValueFrom,
+};
+use futures::{stream, Future, Stream, StreamExt, TryStreamExt};
+use k8s_openapi::api::core::v1::{ConfigMap, Secret};
+use kube::client::Client;
+use kube::core::PartialObjectMeta;
+use kube::runtime::{metadata_watcher, watcher};
+use kube::{Api, Resource, ResourceExt};
+use kube_runtime::reflector::{ObjectRef, Store};
+use kube_runtime::WatchStreamExt;
+use serde::de::DeserializeOwned;
+use std::fmt::Debug;
+use std::hash::Hash;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+/// How many changes a slow controller can fall behind on a shared watch before it misses some
+const SHARED_WATCH_CAPACITY: usize = 1024;
+
+pub fn map_object<TResource>(o: TResource) -> Option<ObjectRef<TResource>>
+where
+    TResource: Resource,
+    TResource::DynamicType: Default,
+{
+    Some(ObjectRef::from_obj(&o))
+}
+
+/// Watches all resources of type `TDependency`, and emits the dependents returned by
+/// `list_dependents` every time one of them changes.
+fn watch_dependents<TDependency, TDependent, ListFn, ListFut>(
+    kubernetes_client: Client,
+    list_dependents: ListFn,
+) -> impl Stream<Item = Result<TDependent, watcher::Error>> + Send + Sized + 'static
+where
+    TDependency: Resource + Clone + DeserializeOwned + Debug + Send + 'static,
+    TDependency::DynamicType: Default,
+    TDependent: Send + 'static,
+    ListFn: FnMut(TDependency) -> ListFut + Send + 'static,
+    ListFut: Future<Output = Result<Vec<TDependent>, watcher::Error>> + Send + 'static,
+{
+    let api: Api<TDependency> = Api::all(kubernetes_client);
+
+    watcher(api, default())
+        .applied_objects()
+        .and_then(list_dependents)
+        .flat_map_unordered(None, |dependents| {
+            stream::iter(dependents.into_iter().flatten().map(Ok))
+        })
+}
+
+pub fn watch_users_for_folder(
+    kubernetes_client: Client,
+) -> impl Stream<Item = Result<SftpgoUser, watcher::Error>> + Send + Sized + 'static {
+    watch_dependents(kubernetes_client, list_users_for_folder)
+}
+
+pub fn watch_users_for_group(
+    kubernetes_client: Client,
+) -> impl Stream<Item = Result<SftpgoUser, watcher::Error>> + Send + Sized + 'static {
+    watch_dependents(kubernetes_client, list_users_for_group)
+}
+
+pub fn watch_users_for_role(
+    kubernetes_client: Client,
+) -> impl Stream<Item = Result<SftpgoUser, watcher::Error>> + Send + Sized + 'static {
+    watch_dependents(kubernetes_client, list_users_for_role)
+}
+
+pub fn watch_admins_for_role(
+    kubernetes_client: Client,
+) -> impl Stream<Item = Result<SftpgoAdmin, watcher::Error>> + Send + Sized + 'static {
+    watch_dependents(kubernetes_client, list_admins_for_role)
+}
+
+pub fn watch_event_rules_for_action(
+    kubernetes_client: Client,
+) -> impl Stream<Item = Result<SftpgoEventRule, watcher::Error>> + Send + Sized + 'static {
+    watch_dependents(kubernetes_client, list_event_rules_for_action)
+}
+
+pub fn watch_shares_for_user(
+    kubernetes_client: Client,
+) -> impl Stream<Item = Result<SftpgoShare, watcher::Error>> + Send + Sized + 'static {
+    watch_dependents(kubernetes_client, list_shares_for_user)
+}
+
+/// Resources that read values from secrets and config maps in their namespace
+pub trait HasValueSources {
+    fn get_value_sources(&self) -> Vec<ValueFrom>;
+}
+
+impl HasValueSources for SftpgoUser {
+    fn get_value_sources(&self) -> Vec<ValueFrom> {
+        let configuration = &self.spec.configuration;
+
+        let mut sources = configuration
+            .filesystem
+            .as_ref()
+            .map(get_file_system_value_sources)
+            .unwrap_or_default();
+
+        if let Some(password_from) = &configuration.password_from {
+            sources.push(ValueFrom::SecretKeyRef {
+                name: password_from.name.clone(),
+                key: password_from.key.clone(),
+            });
+        }
+
+        if let Some(public_keys) = &configuration.public_keys {
+            sources.extend(public_keys.iter().filter_map(|k| match k {
+                ProvidedValue::ValueFrom(value_from) => Some(value_from.clone()),
+                ProvidedValue::Value(_) => None,
+            }));
+        }
+
+        sources
+    }
+}
+
+impl HasValueSources for SftpgoFolder {
+    fn get_value_sources(&self) -> Vec<ValueFrom> {
+        get_file_system_value_sources(&self.spec.configuration.filesystem)
+    }
+}
+
+impl HasValueSources for SftpgoGroup {
+    fn get_value_sources(&self) -> Vec<ValueFrom> {
+        self.spec
+            .configuration
+            .user_settings
+            .as_ref()
+            .and_then(|s| s.filesystem.as_ref())
+            .map(get_file_system_value_sources)
+            .unwrap_or_default()
+    }
+}
+
+impl HasValueSources for SftpgoEventAction {
+    fn get_value_sources(&self) -> Vec<ValueFrom> {
+        match &self.spec.configuration.action {
+            SftpgoEventActionKind::Http(http) => match &http.password {
+                Some(ProvidedValue::ValueFrom(value_from)) => vec![value_from.clone()],
+                _ => vec![],
+            },
+            _ => vec![],
+        }
+    }
+}
+
+/// A single cluster-wide watch of the metadata of `TResource`, shared by the controllers of all
+/// resources depending on it, instead of every controller watching the resources on its own.
+pub struct SharedMetadataWatch<TResource> {
+    sender: broadcast::Sender<PartialObjectMeta<TResource>>,
+}
+
+impl<TResource> SharedMetadataWatch<TResource>
+where
+    TResource: Resource + Clone + DeserializeOwned + Debug + Send + Sync + 'static,
+    TResource::DynamicType: Default,
+{
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(SHARED_WATCH_CAPACITY);
+
+        Self { sender }
+    }
+
+    /// The changes seen by the watch, for `Controller::watches_stream`. Only changes made after
+    /// subscribing are emitted, so subscribe before running the watch.
+    pub fn subscribe(
+        &self,
+    ) -> impl Stream<Item = Result<PartialObjectMeta<TResource>, watcher::Error>> + Send + 'static
+    {
+        stream::unfold(self.sender.subscribe(), |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(object) => return Some((Ok(object), receiver)),
+                    Err(RecvError::Lagged(missed)) => {
+                        warn!(
+                            "Missed {} {} changes, as they arrived faster than they were handled",
+                            missed,
+                            TResource::kind(&default())
+                        );
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+
+    pub async fn run(self, kubernetes_client: Client) {
+        let api: Api<TResource> = Api::all(kubernetes_client);
+
+        let mut changes = metadata_watcher(api, default())
+            .backoff(watcher::default_backoff())
+            .applied_objects()
+            .boxed();
+
+        while let Some(change) = changes.next().await {
+            match change {
+                // Fails when no controller is subscribed, which is fine
+                Ok(object) => _ = self.sender.send(object),
+                Err(e) => warn!("Watching {} failed: {}", TResource::kind(&default()), e),
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ValueSourceKind {
+    Secret,
+    ConfigMap,
+}
+
+/// Maps a secret to the resources reading values from it. The resources are looked up in the
+/// store of their controller, so changes to secrets don't cause requests to kubernetes.
+pub fn map_value_source_secret<TDependent>(
+    store: Store<TDependent>,
+) -> impl Fn(PartialObjectMeta<Secret>) -> Vec<ObjectRef<TDependent>> + Send + Sync + 'static
+where
+    TDependent: HasValueSources + Resource + Clone + Send + Sync + 'static,
+    TDependent::DynamicType: Default + Eq + Hash + Clone,
+{
+    move |secret| {
+        find_value_source_dependents(
+            &store,
+            ValueSourceKind::Secret,
+            &secret.name_any(),
+            &secret.namespace().unwrap_or_default(),
+        )
+    }
+}
+
+/// Maps a config map to the resources reading values from it, like [map_value_source_secret]
+pub fn map_value_source_config_map<TDependent>(
+    store: Store<TDependent>,
+) -> impl Fn(PartialObjectMeta<ConfigMap>) -> Vec<ObjectRef<TDependent>> + Send + Sync + 'static
+where
+    TDependent: HasValueSources + Resource + Clone + Send + Sync + 'static,
+    TDependent::DynamicType: Default + Eq + Hash + Clone,
+{
+    move |config_map| {
+        find_value_source_dependents(
+            &store,
+            ValueSourceKind::ConfigMap,
+            &config_map.name_any(),
+            &config_map.namespace().unwrap_or_default(),
+        )
+    }
+}
+
+fn find_value_source_dependents<TDependent>(
+    store: &Store<TDependent>,
+    kind: ValueSourceKind,
+    source_name: &str,
+    source_namespace: &str,
+) -> Vec<ObjectRef<TDependent>>
+where
+    TDependent: HasValueSources + Resource + Clone,
+    TDependent::DynamicType: Default + Eq + Hash + Clone,
+{
+    store
+        .state()
+        .iter()
+        // Values are always read from the namespace of the resource
+        .filter(|resource| resource.namespace().as_deref() == Some(source_namespace))
+        .filter(|resource| {
+            resource
+                .get_value_sources()
+                .iter()
+                .any(|source| match source {
+                    ValueFrom::SecretKeyRef { name, .. } => {
+                        kind == ValueSourceKind::Secret && name == source_name
+                    }
+                    ValueFrom::ConfigMapKeyRef { name, .. } => {
+                        kind == ValueSourceKind::ConfigMap && name == source_name
+                    }
+                })
+        })
+        .map(|resource| ObjectRef::from_obj(resource.as_ref()))
+        .collect()
+}
+
+async fn list_users_for_folder(folder: SftpgoFolder) -> Result<Vec<SftpgoUser>, watcher::Error> {
+    let folder_name = folder.name_any();
+    let folder_namespace = folder.metadata.namespace.unwrap_or_default();
+
+    list_resources(|user: &SftpgoUser, user_ns| {
+        user.spec
+            .configuration
+            .virtual_folders
+            .as_ref()
+            .is_some_and(|ve| {
+                ve.iter().any(|v| {
+                    references_resource(
+                        &v.name,
+                        &v.namespace,
+                        user_ns,
+                        &folder_name,
+                        &folder_namespace,
+                    )
+                })
+            })
+    })
+    .await
+}
+
+async fn list_users_for_group(group: SftpgoGroup) -> Result<Vec<SftpgoUser>, watcher::Error> {
+    let group_name = group.name_any();
+    let group_namespace = group.metadata.namespace.unwrap_or_default();
+
+    list_resources(|user: &SftpgoUser, user_ns| {
+        user.spec.configuration.groups.as_ref().is_some_and(|ge| {
+            ge.iter().any(|g| {
+                references_resource(
+                    &g.name,
+                    &g.namespace,
+                    user_ns,
+                    &group_name,
+                    &group_namespace,
+                )
+            })
+        })
+    })
+    .await
+}
+
+async fn list_users_for_role(role: SftpgoRole) -> Result<Vec<SftpgoUser>, watcher::Error> {
+    let role_name = role.name_any();
+    let role_namespace = role.metadata.namespace.unwrap_or_default();
+
+    list_resources(|user: &SftpgoUser, user_ns| {
+        references_role(
+            user.spec.configuration.role_reference.as_ref(),
+            user_ns,
+            &role_name,
+            &role_namespace,
+        )
+    })
+    .await
+}
+
+async fn list_admins_for_role(role: SftpgoRole) -> Result<Vec<SftpgoAdmin>, watcher::Error> {
+    let role_name = role.name_any();
+    let role_namespace = role.metadata.namespace.unwrap_or_default();
+
+    list_resources(|admin: &SftpgoAdmin, admin_ns| {
+        references_role(
+            admin.spec.configuration.role_reference.as_ref(),
+            admin_ns,
+            &role_name,
+            &role_namespace,
+        )
+    })
+    .await
+}
+
+fn references_role(
+    reference: Option<&RoleReference>,
+    referrer_namespace: &str,
+    role_name: &str,
+    role_namespace: &str,
+) -> bool {
+    reference.is_some_and(|r| {
+        references_resource(
+            &r.name,
+            &r.namespace,
+            referrer_namespace,
+            role_name,
+            role_namespace,
+        )
+    })
+}
+
+async fn list_event_rules_for_action(
+    action: SftpgoEventAction,
+) -> Result<Vec<SftpgoEventRule>, watcher::Error> {
+    let action_name = action.name_any();
+    let action_namespace = action.metadata.namespace.unwrap_or_default();
+
+    list_resources(|rule: &SftpgoEventRule, rule_ns| {
+        rule.spec.configuration.actions.iter().any(|a| {
+            references_resource(
+                &a.name,
+                &a.namespace,
+                rule_ns,
+                &action_name,
+                &action_namespace,
+            )
+        })
+    })
+    .await
+}
+
+async fn list_shares_for_user(user: SftpgoUser) -> Result<Vec<SftpgoShare>, watcher::Error> {
+    let user_name = user.name_any();
+    let user_namespace = user.metadata.namespace.unwrap_or_default();
+
+    list_resources(|share: &SftpgoShare, share_ns| {
+        let reference = &share.spec.user_reference;
+        references_resource(
+            &reference.name,
+            &reference.namespace,
+            share_ns,
+            &user_name,
+            &user_namespace,
+        )
+    })
+    .await
+}
+
+/// Checks if a reference from a resource in `referrer_namespace` points at the resource
+/// `name` in `namespace`.
+fn references_resource(
+    reference_name: &str,
+    reference_namespace: &Option<String>,
+    referrer_namespace: &str,
+    name: &str,
+    namespace: &str,
+) -> bool {
+    if reference_name != name {
+        return false;
+    }
+
+    if let Some(ns) = reference_namespace {
+        ns == namespace
+    } else {
+        referrer_namespace == namespace
+    }
+}
+
+async fn list_resources<TResource, F>(filter: F) -> Result<Vec<TResource>, watcher::Error>
+where
+    TResource: Resource + Clone + DeserializeOwned + Debug,
+    TResource::DynamicType: Default,
+    F: Fn(&TResource, &str) -> bool,
+{
+    info!("Getting {} list", TResource::kind(&default()));
+    let kubernetes_client = Client::try_default()
+        .await
+        .expect("Expected a valid KUBECONFIG environment variable.");
+
+    let api: Api<TResource> = Api::all(kubernetes_client);
+    let response = api.list(&default()).await.map_err(|e| {
+        watcher::Error::WatchError(kube::core::ErrorResponse {
+            code: 0,
+            message: e.to_string(),
+            reason: "".to_string(),
+            status: "".to_string(),
+        })
+    })?;
+
+    let items = response
+        .items
+        .into_iter()
+        .filter(|r| {
+            if let Some(ns) = r.namespace() {
+                filter(r, &ns)
+            } else {
+                false
+            }
+        })
+        .collect();
+
+    Ok(items)
+}
@@ -0,0 +1,201 @@
+use serde::Serialize;
+use serde_json::Value;
+
+/// Compares the request with what SFTPGo returns for the resource, and returns the paths of the
+/// fields that differ. Only fields in the request are compared, so server managed fields like ids
+/// and usage are ignored. Fields the response doesn't contain, like passwords, and secrets SFTPGo
+/// redacts can't be compared either. With `compare_unset`, fields the request leaves unset have
+/// drifted when SFTPGo has a value for them, as updating resets them.
+pub fn find_drift<TRequest: Serialize, TResponse: Serialize>(
+    request: &TRequest,
+    response: &TResponse,
+    compare_unset: bool,
+) -> Vec<String> {
+    let (Ok(desired), Ok(actual)) = (
+        serde_json::to_value(request),
+        serde_json::to_value(response),
+    ) else {
+        return vec![];
+    };
+
+    let mut drifted = vec![];
+    compare(&desired, &actual, "", compare_unset, &mut drifted);
+
+    drifted
+}
+
+/// The top level field a path returned by `find_drift` belongs to
+pub fn top_level_field(path: &str) -> &str {
+    path.split(['.', '[']).next().unwrap_or(path)
+}
+
+fn compare(
+    desired: &Value,
+    actual: &Value,
+    path: &str,
+    compare_unset: bool,
+    drifted: &mut Vec<String>,
+) {
+    match (desired, actual) {
+        (Value::Null, actual) => {
+            if compare_unset && !is_empty(actual) {
+                drifted.push(path.to_string());
+            }
+        }
+        // SFTPGo leaves out empty values
+        (desired, Value::Null) if is_empty(desired) => {}
+        (Value::Object(_), actual) if is_redacted(actual) => {}
+        (Value::Object(desired), Value::Object(actual)) => {
+            for (key, desired_value) in desired {
+                if let Some(actual_value) = actual.get(key) {
+                    compare(
+                        desired_value,
+                        actual_value,
+                        &join(path, key),
+                        compare_unset,
+                        drifted,
+                    );
+                }
+            }
+        }
+        (Value::Array(desired_values), Value::Array(actual_values)) => {
+            if desired_values.len() != actual_values.len() {
+                drifted.push(path.to_string());
+            } else if desired_values.iter().all(is_scalar) {
+                // Lists of values, like permissions, aren't guaranteed to keep their order
+                let mut desired_values: Vec<String> =
+                    desired_values.iter().map(Value::to_string).collect();
+                let mut actual_values: Vec<String> =
+                    actual_values.iter().map(Value::to_string).collect();
+                desired_values.sort();
+                actual_values.sort();
+
+                if desired_values != actual_values {
+                    drifted.push(path.to_string());
+                }
+            } else {
+                for (index, (desired_value, actual_value)) in
+                    desired_values.iter().zip(actual_values).enumerate()
+                {
+                    compare(
+                        desired_value,
+                        actual_value,
+                        &format!("{}[{}]", path, index),
+                        compare_unset,
+                        drifted,
+                    );
+                }
+            }
+        }
+        (Value::Number(desired), Value::Number(actual)) => {
+            if desired.as_f64() != actual.as_f64() {
+                drifted.push(path.to_string());
+            }
+        }
+        (desired, actual) => {
+            if desired != actual {
+                drifted.push(path.to_string());
+            }
+        }
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Bool(b) => !b,
+        Value::Number(n) => n.as_f64() == Some(0.0),
+        // SFTPGo stores "None" for some choices left unset, like the TLS username
+        Value::String(s) => s.is_empty() || s == "None",
+        Value::Array(a) => a.is_empty(),
+        Value::Object(o) => o.values().all(is_empty),
+    }
+}
+
+fn is_scalar(value: &Value) -> bool {
+    !matches!(value, Value::Array(_) | Value::Object(_))
+}
+
+/// SFTPGo never returns the payload of secrets, only that there is one
+fn is_redacted(value: &Value) -> bool {
+    value.get("status").and_then(Value::as_str) == Some("Redacted")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn ignores_server_managed_and_redacted_fields() {
+        let request = json!({
+            "username": "test",
+            "description": null,
+            "password": "secret",
+            "filesystem": {"secret": {"status": "Plain", "payload": "value"}},
+            "permissions": {"/": ["list", "download"]},
+        });
+        let response = json!({
+            "id": 1,
+            "username": "test",
+            "description": "set in the web admin",
+            "filesystem": {"secret": {"status": "Redacted", "payload": ""}},
+            "permissions": {"/": ["download", "list"]},
+        });
+
+        assert!(find_drift(&request, &response, false).is_empty());
+    }
+
+    #[test]
+    fn finds_values_set_for_unset_fields_only_when_comparing_them() {
+        let request = json!({
+            "username": "test",
+            "description": null,
+            "email": null,
+            "quota_size": null,
+        });
+        let response = json!({
+            "username": "test",
+            "description": "set in the web admin",
+            "email": "",
+            "quota_size": 0,
+        });
+
+        assert!(find_drift(&request, &response, false).is_empty());
+        assert_eq!(find_drift(&request, &response, true), vec!["description"]);
+    }
+
+    #[test]
+    fn finds_changed_fields() {
+        let request = json!({
+            "status": 1,
+            "quota_size": 100,
+            "filters": {"allowed_ip": ["10.0.0.0/8"], "denied_ip": []},
+            "virtual_folders": [{"name": "folder", "virtual_path": "/a"}],
+        });
+        let response = json!({
+            "status": 0,
+            "quota_size": null,
+            "filters": {"allowed_ip": ["0.0.0.0/0"], "denied_ip": null},
+            "virtual_folders": [{"name": "folder", "virtual_path": "/b"}],
+        });
+
+        assert_eq!(
+            find_drift(&request, &response, false),
+            vec![
+                "status",
+                "quota_size",
+                "filters.allowed_ip",
+                "virtual_folders[0].virtual_path"
+            ]
+        );
+    }
+}
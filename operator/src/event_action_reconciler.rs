@@ -0,0 +1,138 @@
+use crate::default;
+use crate::reconciler::{ContextData, SftpgoResource};
+use crate::value_from::get_provided_value;
+use crate::Error;
+use async_trait::async_trait;
+use crds::{
+    FieldManagement, HttpMethod, ServerReference, SftpgoEventAction, SftpgoEventActionKind,
+};
+use sftpgo_client::event_actions::{
+    EmailContentType, EventActionCommandConfig, EventActionDataRetentionConfig,
+    EventActionEmailConfig, EventActionHttpConfig, EventActionOptions, EventActionRequest,
+    EventActionResponse, EventActionType, FolderRetention, KeyValue,
+};
+use sftpgo_client::filesystem::{SftpgoSecret, SftpgoSecretStatus};
+use std::collections::BTreeMap;
+
+const DEFAULT_TIMEOUT: i32 = 20;
+
+fn to_key_values(values: Option<&BTreeMap<String, String>>) -> Option<Vec<KeyValue>> {
+    values.map(|v| {
+        v.iter()
+            .map(|(key, value)| KeyValue {
+                key: key.clone(),
+                value: value.clone(),
+            })
+            .collect()
+    })
+}
+
+#[async_trait]
+impl SftpgoResource for SftpgoEventAction {
+    type Request = EventActionRequest;
+    type Response = EventActionResponse;
+
+    fn get_name(&self) -> &str {
+        &self.spec.configuration.name
+    }
+
+    async fn get_request(
+        &self,
+        context: &ContextData,
+        namespace: &str,
+    ) -> Result<Self::Request, Error> {
+        let action_configuration = &self.spec.configuration;
+
+        let mut options: EventActionOptions = default();
+
+        let action_type = match &action_configuration.action {
+            SftpgoEventActionKind::Http(http) => {
+                options.http_config = Some(EventActionHttpConfig {
+                    endpoint: http.endpoint.clone(),
+                    username: http.username.clone(),
+                    password: match &http.password {
+                        Some(password) => Some(SftpgoSecret {
+                            status: SftpgoSecretStatus::Plain,
+                            payload: get_provided_value(password, context, namespace).await?,
+                            ..default()
+                        }),
+                        None => None,
+                    },
+                    headers: to_key_values(http.headers.as_ref()),
+                    timeout: http.timeout.unwrap_or(DEFAULT_TIMEOUT),
+                    skip_tls_verify: http.skip_tls_verify,
+                    method: match http.method.unwrap_or_default() {
+                        HttpMethod::Get => "GET",
+                        HttpMethod::Post => "POST",
+                        HttpMethod::Put => "PUT",
+                        HttpMethod::Delete => "DELETE",
+                    }
+                    .to_string(),
+                    query_parameters: to_key_values(http.query_parameters.as_ref()),
+                    body: http.body.clone(),
+                });
+                EventActionType::Http
+            }
+            SftpgoEventActionKind::Command(command) => {
+                options.cmd_config = Some(EventActionCommandConfig {
+                    cmd: command.cmd.clone(),
+                    args: command.args.clone(),
+                    timeout: command.timeout.unwrap_or(DEFAULT_TIMEOUT),
+                    env_vars: to_key_values(command.env_vars.as_ref()),
+                });
+                EventActionType::Command
+            }
+            SftpgoEventActionKind::Email(email) => {
+                options.email_config = Some(EventActionEmailConfig {
+                    recipients: email.recipients.clone(),
+                    bcc: email.bcc.clone(),
+                    subject: email.subject.clone(),
+                    body: email.body.clone(),
+                    content_type: if email.html.unwrap_or(false) {
+                        EmailContentType::TextHtml
+                    } else {
+                        EmailContentType::TextPlain
+                    },
+                    attachments: email.attachments.clone(),
+                });
+                EventActionType::Email
+            }
+            SftpgoEventActionKind::DataRetentionCheck(retention) => {
+                options.retention_config = Some(EventActionDataRetentionConfig {
+                    folders: retention
+                        .folders
+                        .iter()
+                        .map(|f| FolderRetention {
+                            path: f.path.clone(),
+                            retention: f.retention,
+                            delete_empty_dirs: f.delete_empty_dirs,
+                            ignore_user_permissions: f.ignore_user_permissions,
+                        })
+                        .collect(),
+                });
+                EventActionType::DataRetentionCheck
+            }
+            SftpgoEventActionKind::Backup {} => EventActionType::Backup,
+            SftpgoEventActionKind::UserQuotaReset {} => EventActionType::UserQuotaReset,
+            SftpgoEventActionKind::FolderQuotaReset {} => EventActionType::FolderQuotaReset,
+            SftpgoEventActionKind::TransferQuotaReset {} => EventActionType::TransferQuotaReset,
+        };
+
+        let request = EventActionRequest {
+            name: action_configuration.name.clone(),
+            description: action_configuration.description.clone(),
+            action_type,
+            options,
+        };
+
+        Ok(request)
+    }
+
+    fn get_server_reference(&self) -> &ServerReference {
+        &self.spec.server_reference
+    }
+
+    fn get_field_management(&self) -> FieldManagement {
+        self.spec.field_management.unwrap_or_default()
+    }
+}
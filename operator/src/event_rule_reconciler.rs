@@ -0,0 +1,163 @@
+use crate::default;
+use crate::reconciler::{ContextData, SftpgoResource};
+use crate::user_reconciler::get_enabled_status;
+use crate::Error;
+use async_trait::async_trait;
+use crds::{
+    EventActionReference, EventConditionPattern, FieldManagement, ServerReference,
+    SftpgoEventAction, SftpgoEventRule, SftpgoEventTrigger, SftpgoStatus,
+};
+use kube::Api;
+use sftpgo_client::event_rules::{
+    ConditionPattern, EventActionMapping, EventActionMappingOptions, EventConditionOptions,
+    EventConditions, EventRuleRequest, EventRuleResponse, EventTrigger, Schedule,
+};
+
+async fn get_event_action_name(
+    namespace: &str,
+    crd_ref: &EventActionReference,
+    context: &ContextData,
+) -> Result<String, Error> {
+    let ns = crd_ref.namespace.as_deref().unwrap_or(namespace);
+
+    let action_client: Api<SftpgoEventAction> =
+        Api::namespaced(context.kubernetes_client.clone(), ns);
+
+    if let Some(action) = action_client.get_opt(&crd_ref.name).await? {
+        if let Some(status) = action.status {
+            if status.get_id().is_none() {
+                return Err(Error::NotReady(crd_ref.name.to_string()));
+            }
+
+            Ok(status.get_last_name().to_string())
+        } else {
+            Err(Error::NotReady(crd_ref.name.to_string()))
+        }
+    } else {
+        Err(Error::UserInput(format!(
+            "Event action {} not found in namespace {}",
+            crd_ref.name, ns
+        )))
+    }
+}
+
+fn map_patterns(patterns: Option<&Vec<EventConditionPattern>>) -> Option<Vec<ConditionPattern>> {
+    patterns.map(|p| {
+        p.iter()
+            .map(|pattern| ConditionPattern {
+                pattern: pattern.pattern.clone(),
+                inverse_match: pattern.inverse_match,
+            })
+            .collect()
+    })
+}
+
+#[async_trait]
+impl SftpgoResource for SftpgoEventRule {
+    type Request = EventRuleRequest;
+    type Response = EventRuleResponse;
+
+    fn get_name(&self) -> &str {
+        &self.spec.configuration.name
+    }
+
+    async fn get_request(
+        &self,
+        context: &ContextData,
+        namespace: &str,
+    ) -> Result<Self::Request, Error> {
+        let rule_configuration = &self.spec.configuration;
+
+        let mut conditions = EventConditions {
+            options: rule_configuration
+                .conditions
+                .as_ref()
+                .map(|c| EventConditionOptions {
+                    names: map_patterns(c.names.as_ref()),
+                    group_names: map_patterns(c.group_names.as_ref()),
+                    role_names: map_patterns(c.role_names.as_ref()),
+                    fs_paths: map_patterns(c.fs_paths.as_ref()),
+                    protocols: c.protocols.clone(),
+                    provider_objects: None,
+                    min_file_size: c.min_file_size,
+                    max_file_size: c.max_file_size,
+                    concurrent_execution: c.concurrent_execution,
+                }),
+            ..default()
+        };
+
+        let trigger = match &rule_configuration.trigger {
+            SftpgoEventTrigger::Filesystem { events } => {
+                conditions.fs_events = Some(events.iter().map(|e| e.to_string()).collect());
+                EventTrigger::FilesystemEvent
+            }
+            SftpgoEventTrigger::Provider { events, objects } => {
+                conditions.provider_events = Some(events.iter().map(|e| e.to_string()).collect());
+
+                if let Some(objects) = objects {
+                    conditions
+                        .options
+                        .get_or_insert_with(default)
+                        .provider_objects = Some(objects.iter().map(|o| o.to_string()).collect());
+                }
+
+                EventTrigger::ProviderEvent
+            }
+            SftpgoEventTrigger::Schedule { schedules } => {
+                conditions.schedules = Some(
+                    schedules
+                        .iter()
+                        .map(|s| Schedule {
+                            hour: s.hour.clone(),
+                            day_of_week: s.day_of_week.clone().unwrap_or("*".to_string()),
+                            day_of_month: s.day_of_month.clone().unwrap_or("*".to_string()),
+                            month: s.month.clone().unwrap_or("*".to_string()),
+                        })
+                        .collect(),
+                );
+                EventTrigger::Schedule
+            }
+            SftpgoEventTrigger::IpBlocked {} => EventTrigger::IpBlocked,
+            SftpgoEventTrigger::CertificateRenewal {} => EventTrigger::CertificateRenewal,
+            SftpgoEventTrigger::OnDemand {} => EventTrigger::OnDemand,
+        };
+
+        let mut actions = Vec::new();
+
+        for (index, action) in rule_configuration.actions.iter().enumerate() {
+            let name = get_event_action_name(namespace, action, context).await?;
+
+            actions.push(EventActionMapping {
+                name,
+                order: index as i32 + 1,
+                options: Some(EventActionMappingOptions {
+                    is_failure_action: action.is_failure_action,
+                    stop_on_failure: action.stop_on_failure,
+                    execute_sync: action.execute_sync,
+                }),
+            });
+        }
+
+        let request = EventRuleRequest {
+            name: rule_configuration.name.clone(),
+            description: rule_configuration.description.clone(),
+            status: get_enabled_status(
+                rule_configuration.enabled.as_ref(),
+                self.get_field_management(),
+            ),
+            trigger,
+            conditions,
+            actions,
+        };
+
+        Ok(request)
+    }
+
+    fn get_server_reference(&self) -> &ServerReference {
+        &self.spec.server_reference
+    }
+
+    fn get_field_management(&self) -> FieldManagement {
+        self.spec.field_management.unwrap_or_default()
+    }
+}
@@ -0,0 +1,64 @@
+use crate::default;
+use crate::reconciler::ContextData;
+use kube::runtime::events::{Event, EventType, Recorder, Reporter};
+use kube::{Resource, ResourceExt};
+
+/// Events.k8s.io limits the note of an event to 1kB
+const MAX_NOTE_LENGTH: usize = 1024;
+
+pub fn get_reporter() -> Reporter {
+    Reporter {
+        controller: "sftpgo-operator".to_string(),
+        // Set to the pod name by kubernetes
+        instance: std::env::var("HOSTNAME").ok(),
+    }
+}
+
+/// Publishes an event about the resource, shown by `kubectl describe`. Failing to publish is
+/// only logged, as events are purely informational.
+pub async fn publish_event<TResource>(
+    context: &ContextData,
+    resource: &TResource,
+    type_: EventType,
+    reason: &str,
+    action: &str,
+    note: String,
+) where
+    TResource: Resource,
+    TResource::DynamicType: Default,
+{
+    let recorder = Recorder::new(
+        context.kubernetes_client.clone(),
+        context.reporter.clone(),
+        resource.object_ref(&default()),
+    );
+
+    let event = Event {
+        type_,
+        reason: reason.to_string(),
+        note: Some(truncate_note(note)),
+        action: action.to_string(),
+        secondary: None,
+    };
+
+    if let Err(e) = recorder.publish(event).await {
+        warn!(
+            "Failed to publish event {} for {}: {}",
+            reason,
+            resource.name_any(),
+            e
+        );
+    }
+}
+
+fn truncate_note(mut note: String) -> String {
+    if note.len() > MAX_NOTE_LENGTH {
+        let mut end = MAX_NOTE_LENGTH;
+        while !note.is_char_boundary(end) {
+            end -= 1;
+        }
+        note.truncate(end);
+    }
+
+    note
+}
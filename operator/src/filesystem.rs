@@ -1,17 +1,23 @@
 use crate::default;
-use crate::reconciler::Error;
+use crate::reconciler::{ContextData, Error};
+use crate::secret_hash::hash_secret;
+use crate::value_from::{get_provided_value, get_value_from};
 use crds::{
-    AzureBlobStorageAccessTier as CrdAccessTier, AzureBlobStorageAuthorization,
-    FileSystem as CrdFileSystem,
+    AzureBlobStorageAccessTier as CrdAccessTier, AzureBlobStorageAuthorization, EqualityCheckMode,
+    FileSystem as CrdFileSystem, ProvidedValue, ValueFrom,
 };
 use sftpgo_client::filesystem::{
     FileSystem as ClientFileSystem, FileSystemConfigAzureBlobStorage,
     FileSystemConfigAzureBlobStorageAccessTier, FileSystemConfigAzureBlobStorageAuthorization,
-    FileSystemOsConfig, FileSystemProvider, SftpgoSecret, SftpgoSecretStatus,
+    FileSystemConfigGoogleCloudStorage, FileSystemConfigHttp, FileSystemConfigS3,
+    FileSystemConfigSftp, FileSystemCryptConfig, FileSystemEqualityCheckMode, FileSystemOsConfig,
+    FileSystemProvider, GoogleCloudStorageAutomaticCredentials, SftpgoSecret, SftpgoSecretStatus,
 };
 
 pub async fn calculate_file_system(
     filesystem: Option<&CrdFileSystem>,
+    context: &ContextData,
+    namespace: &str,
 ) -> Result<ClientFileSystem, Error> {
     let fs = match filesystem {
         Some(CrdFileSystem::Local {
@@ -25,48 +31,176 @@ pub async fn calculate_file_system(
             }),
             ..default()
         },
-        Some(CrdFileSystem::AzureBlobStorage(blob)) => ClientFileSystem {
-            provider: FileSystemProvider::AzureBlobStorage,
-            az_blob_config: Some(FileSystemConfigAzureBlobStorage {
-                auth: match &blob.authorization {
-                    AzureBlobStorageAuthorization::SharedKey {
-                        account_key,
-                        account_name,
-                        container,
-                    } => FileSystemConfigAzureBlobStorageAuthorization::SharedKey {
-                        account_name: account_name.clone(),
-                        container: container.clone(),
-                        account_key: SftpgoSecret {
-                            status: SftpgoSecretStatus::Plain,
-                            payload: account_key.clone(),
-                            ..default()
-                        },
-                    },
-                    AzureBlobStorageAuthorization::SharedAccessSignatureUrl(url) => {
-                        FileSystemConfigAzureBlobStorageAuthorization::SharedAccessSignatureUrl {
-                            sas_url: SftpgoSecret {
-                                status: SftpgoSecretStatus::Plain,
-                                payload: url.clone(),
-                                ..default()
-                            },
-                        }
-                    }
+        Some(CrdFileSystem::AzureBlobStorage(blob)) => {
+            let auth = match &blob.authorization {
+                AzureBlobStorageAuthorization::SharedKey {
+                    account_key,
+                    account_name,
+                    container,
+                } => FileSystemConfigAzureBlobStorageAuthorization::SharedKey {
+                    account_name: account_name.clone(),
+                    container: container.clone(),
+                    account_key: get_sftpgo_secret(account_key, context, namespace).await?,
                 },
-                endpoint: blob.endpoint.clone(),
-                upload_part_size: blob.upload_part_size.clone(),
-                upload_concurrency: blob.upload_concurrency.clone(),
-                download_part_size: blob.download_part_size.clone(),
-                download_concurrency: blob.download_concurrency.clone(),
-                access_tier: blob.access_tier.map(|t| match t {
-                    CrdAccessTier::Hot => FileSystemConfigAzureBlobStorageAccessTier::Hot,
-                    CrdAccessTier::Cool => FileSystemConfigAzureBlobStorageAccessTier::Cool,
-                    CrdAccessTier::Archive => FileSystemConfigAzureBlobStorageAccessTier::Archive,
+                AzureBlobStorageAuthorization::SharedAccessSignatureUrl(url) => {
+                    FileSystemConfigAzureBlobStorageAuthorization::SharedAccessSignatureUrl {
+                        sas_url: get_sftpgo_secret(url, context, namespace).await?,
+                    }
+                }
+            };
+
+            ClientFileSystem {
+                provider: FileSystemProvider::AzureBlobStorage,
+                az_blob_config: Some(FileSystemConfigAzureBlobStorage {
+                    auth,
+                    endpoint: blob.endpoint.clone(),
+                    upload_part_size: blob.upload_part_size,
+                    upload_concurrency: blob.upload_concurrency,
+                    download_part_size: blob.download_part_size,
+                    download_concurrency: blob.download_concurrency,
+                    access_tier: blob.access_tier.map(|t| match t {
+                        CrdAccessTier::Hot => FileSystemConfigAzureBlobStorageAccessTier::Hot,
+                        CrdAccessTier::Cool => FileSystemConfigAzureBlobStorageAccessTier::Cool,
+                        CrdAccessTier::Archive => {
+                            FileSystemConfigAzureBlobStorageAccessTier::Archive
+                        }
+                    }),
+                    key_prefix: blob.key_prefix.clone(),
+                    use_emulator: blob.use_emulator,
+                }),
+                ..default()
+            }
+        }
+        Some(CrdFileSystem::S3(s3)) => ClientFileSystem {
+            provider: FileSystemProvider::S3,
+            s3_config: Some(FileSystemConfigS3 {
+                bucket: s3.bucket.clone(),
+                region: s3.region.clone(),
+                endpoint: s3.endpoint.clone(),
+                access_key: get_optional_provided_value(s3.access_key.as_ref(), context, namespace)
+                    .await?,
+                access_secret: get_optional_sftpgo_secret(
+                    s3.access_secret.as_ref(),
+                    context,
+                    namespace,
+                )
+                .await?,
+                role_arn: s3.role_arn.clone(),
+                storage_class: s3.storage_class.clone(),
+                acl: s3.acl.clone(),
+                upload_part_size: s3.upload_part_size,
+                upload_concurrency: s3.upload_concurrency,
+                upload_part_max_time: s3.upload_part_max_time,
+                download_part_size: s3.download_part_size,
+                download_concurrency: s3.download_concurrency,
+                download_part_max_time: s3.download_part_max_time,
+                force_path_style: s3.force_path_style,
+                key_prefix: s3.key_prefix.clone(),
+            }),
+            ..default()
+        },
+        Some(CrdFileSystem::GoogleCloudStorage(gcs)) => {
+            let credentials =
+                get_optional_sftpgo_secret(gcs.credentials.as_ref(), context, namespace).await?;
+            let automatic_credentials = if credentials.is_some() {
+                GoogleCloudStorageAutomaticCredentials::Disabled
+            } else {
+                GoogleCloudStorageAutomaticCredentials::Enabled
+            };
+
+            ClientFileSystem {
+                provider: FileSystemProvider::GoogleCloudStorage,
+                gcs_config: Some(FileSystemConfigGoogleCloudStorage {
+                    bucket: gcs.bucket.clone(),
+                    credentials,
+                    automatic_credentials,
+                    storage_class: gcs.storage_class.clone(),
+                    acl: gcs.acl.clone(),
+                    upload_part_size: gcs.upload_part_size,
+                    upload_part_max_time: gcs.upload_part_max_time,
+                    key_prefix: gcs.key_prefix.clone(),
                 }),
-                key_prefix: blob.key_prefix.clone(),
-                use_emulator: blob.use_emulator,
+                ..default()
+            }
+        }
+        Some(CrdFileSystem::Sftp(sftp)) => {
+            if sftp.password.is_none() && sftp.private_key.is_none() {
+                return Err(Error::UserInput(format!(
+                    "The sftp filesystem for {} needs either a password or a private key",
+                    sftp.endpoint
+                )));
+            }
+
+            ClientFileSystem {
+                provider: FileSystemProvider::Sftp,
+                sftp_config: Some(FileSystemConfigSftp {
+                    endpoint: sftp.endpoint.clone(),
+                    username: sftp.username.clone(),
+                    password: get_optional_sftpgo_secret(
+                        sftp.password.as_ref(),
+                        context,
+                        namespace,
+                    )
+                    .await?,
+                    private_key: get_optional_sftpgo_secret(
+                        sftp.private_key.as_ref(),
+                        context,
+                        namespace,
+                    )
+                    .await?,
+                    key_passphrase: get_optional_sftpgo_secret(
+                        sftp.key_passphrase.as_ref(),
+                        context,
+                        namespace,
+                    )
+                    .await?,
+                    fingerprints: sftp.fingerprints.clone(),
+                    prefix: sftp.prefix.clone(),
+                    disable_concurrent_reads: sftp.disable_concurrent_reads,
+                    buffer_size: sftp.buffer_size,
+                    equality_check_mode: map_equality_check_mode(sftp.equality_check_mode),
+                }),
+                ..default()
+            }
+        }
+        Some(CrdFileSystem::Http(http)) => ClientFileSystem {
+            provider: FileSystemProvider::Http,
+            http_config: Some(FileSystemConfigHttp {
+                endpoint: http.endpoint.clone(),
+                username: http.username.clone(),
+                password: get_optional_sftpgo_secret(http.password.as_ref(), context, namespace)
+                    .await?,
+                api_key: get_optional_sftpgo_secret(http.api_key.as_ref(), context, namespace)
+                    .await?,
+                skip_tls_verify: http.skip_tls_verify,
+                equality_check_mode: map_equality_check_mode(http.equality_check_mode),
             }),
             ..default()
         },
+        Some(CrdFileSystem::LocalEncrypted {
+            passphrase,
+            read_buffer_size,
+            write_buffer_size,
+        }) => {
+            let passphrase = ValueFrom::SecretKeyRef {
+                name: passphrase.name.clone(),
+                key: passphrase.key.clone(),
+            };
+
+            ClientFileSystem {
+                provider: FileSystemProvider::LocalFileSystemEncrypted,
+                crypt_config: Some(FileSystemCryptConfig {
+                    passphrase: SftpgoSecret {
+                        status: SftpgoSecretStatus::Plain,
+                        payload: get_value_from(&passphrase, context, namespace).await?,
+                        ..default()
+                    },
+                    read_buffer_size: *read_buffer_size,
+                    write_buffer_size: *write_buffer_size,
+                }),
+                ..default()
+            }
+        }
         None => ClientFileSystem {
             provider: FileSystemProvider::LocalFilesystem,
             ..default()
@@ -75,3 +209,174 @@ pub async fn calculate_file_system(
 
     Ok(fs)
 }
+
+/// Gets a hash of the passphrase of an encrypted filesystem, to detect changes to it
+pub fn get_passphrase_hash(filesystem: &ClientFileSystem, salt: &str) -> Option<String> {
+    filesystem
+        .crypt_config
+        .as_ref()
+        .map(|c| hash_secret(salt, &c.passphrase.payload))
+}
+
+/// Files stored in an encrypted filesystem can't be read with another passphrase, so the
+/// passphrase can't be changed, and encryption can't be turned on or off, while the resource has
+/// files in SFTPGo, unless explicitly allowed. Without a recorded hash the passphrase in SFTPGo is
+/// unknown, so the current one is adopted.
+pub fn check_passphrase_change(
+    name: &str,
+    previous_hash: Option<&str>,
+    filesystem: &ClientFileSystem,
+    salt: &str,
+    allow_passphrase_change: bool,
+    has_files: bool,
+) -> Result<(), Error> {
+    let Some(previous_hash) = previous_hash else {
+        return Ok(());
+    };
+
+    let current_hash = get_passphrase_hash(filesystem, salt);
+
+    if allow_passphrase_change || !has_files || Some(previous_hash) == current_hash.as_deref() {
+        return Ok(());
+    }
+
+    Err(Error::UserInput(format!(
+        "The passphrase of the encrypted filesystem of {} can't be changed, as existing files \
+         could no longer be read. Restore the previous passphrase in the referenced secret, or set \
+         allowPassphraseChange if the change is intended.",
+        name
+    )))
+}
+
+/// Whether SFTPGo has files stored for a user or folder, going by its used quota. The used quota
+/// is only tracked when quota tracking is enabled on the server.
+pub fn has_stored_files(used_quota_size: Option<i64>, used_quota_files: Option<i32>) -> bool {
+    used_quota_size.unwrap_or_default() > 0 || used_quota_files.unwrap_or_default() > 0
+}
+
+async fn get_sftpgo_secret(
+    value: &ProvidedValue<String>,
+    context: &ContextData,
+    namespace: &str,
+) -> Result<SftpgoSecret, Error> {
+    Ok(SftpgoSecret {
+        status: SftpgoSecretStatus::Plain,
+        payload: get_provided_value(value, context, namespace).await?,
+        ..default()
+    })
+}
+
+async fn get_optional_sftpgo_secret(
+    value: Option<&ProvidedValue<String>>,
+    context: &ContextData,
+    namespace: &str,
+) -> Result<Option<SftpgoSecret>, Error> {
+    if let Some(value) = value {
+        Ok(Some(get_sftpgo_secret(value, context, namespace).await?))
+    } else {
+        Ok(None)
+    }
+}
+
+async fn get_optional_provided_value(
+    value: Option<&ProvidedValue<String>>,
+    context: &ContextData,
+    namespace: &str,
+) -> Result<Option<String>, Error> {
+    if let Some(value) = value {
+        Ok(Some(get_provided_value(value, context, namespace).await?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Lists the secrets and config maps the filesystem reads values from. They are always in the
+/// namespace of the resource the filesystem belongs to.
+pub fn get_value_sources(filesystem: &CrdFileSystem) -> Vec<ValueFrom> {
+    let provided: Vec<&ProvidedValue<String>> = match filesystem {
+        CrdFileSystem::Local { .. } => vec![],
+        CrdFileSystem::AzureBlobStorage(blob) => match &blob.authorization {
+            AzureBlobStorageAuthorization::SharedKey { account_key, .. } => vec![account_key],
+            AzureBlobStorageAuthorization::SharedAccessSignatureUrl(url) => vec![url],
+        },
+        CrdFileSystem::S3(s3) => s3
+            .access_key
+            .iter()
+            .chain(s3.access_secret.iter())
+            .collect(),
+        CrdFileSystem::GoogleCloudStorage(gcs) => gcs.credentials.iter().collect(),
+        CrdFileSystem::Sftp(sftp) => sftp
+            .password
+            .iter()
+            .chain(sftp.private_key.iter())
+            .chain(sftp.key_passphrase.iter())
+            .collect(),
+        CrdFileSystem::Http(http) => http.password.iter().chain(http.api_key.iter()).collect(),
+        CrdFileSystem::LocalEncrypted { passphrase, .. } => {
+            return vec![ValueFrom::SecretKeyRef {
+                name: passphrase.name.clone(),
+                key: passphrase.key.clone(),
+            }]
+        }
+    };
+
+    provided
+        .into_iter()
+        .filter_map(|p| match p {
+            ProvidedValue::Value(_) => None,
+            ProvidedValue::ValueFrom(value_from) => Some(value_from.clone()),
+        })
+        .collect()
+}
+
+fn map_equality_check_mode(mode: Option<EqualityCheckMode>) -> FileSystemEqualityCheckMode {
+    match mode.unwrap_or_default() {
+        EqualityCheckMode::EndpointAndUsername => FileSystemEqualityCheckMode::EndpointAndUsername,
+        EqualityCheckMode::Endpoint => FileSystemEqualityCheckMode::Endpoint,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encrypted(passphrase: &str) -> ClientFileSystem {
+        ClientFileSystem {
+            provider: FileSystemProvider::LocalFileSystemEncrypted,
+            crypt_config: Some(FileSystemCryptConfig {
+                passphrase: SftpgoSecret {
+                    status: SftpgoSecretStatus::Plain,
+                    payload: passphrase.to_string(),
+                    ..default()
+                },
+                read_buffer_size: None,
+                write_buffer_size: None,
+            }),
+            ..default()
+        }
+    }
+
+    #[test]
+    fn passphrase_change_requires_opt_in() {
+        let previous = get_passphrase_hash(&encrypted("old"), "salt");
+        let previous = previous.as_deref();
+
+        let check = |previous, filesystem: ClientFileSystem, allow, has_files| {
+            check_passphrase_change("test", previous, &filesystem, "salt", allow, has_files)
+        };
+
+        assert!(check(previous, encrypted("old"), false, true).is_ok());
+        assert!(check(previous, encrypted("new"), false, true).is_err());
+        assert!(check(previous, encrypted("new"), true, true).is_ok());
+
+        // Turning encryption off changes how existing files are read as well
+        assert!(check(previous, default(), false, true).is_err());
+
+        // Without files there is nothing that could no longer be read
+        assert!(check(previous, encrypted("new"), false, false).is_ok());
+
+        // Without a recorded hash the passphrase in SFTPGo is adopted
+        assert!(check(None, encrypted("new"), false, true).is_ok());
+        assert!(check(None, default(), false, true).is_ok());
+    }
+}
@@ -44,12 +44,7 @@ where
         ))
     })?;
 
-    if resource
-        .meta()
-        .finalizers
-        .as_ref()
-        .map_or(true, |finalizers| finalizers.is_empty())
-    {
+    if resource.finalizers().is_empty() {
         debug!("Finalizer not found on resource {namespace}/{name}, adding");
         let resource = add_finalizer::<TResource>(client, &name, &namespace).await?;
         debug!("Finalizer added to {namespace}/{name}");
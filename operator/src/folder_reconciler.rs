@@ -1,8 +1,11 @@
-use crate::filesystem::calculate_file_system;
+use crate::filesystem::{
+    calculate_file_system, check_passphrase_change, get_passphrase_hash, has_stored_files,
+};
 use crate::reconciler::{ContextData, SftpgoResource};
 use crate::Error;
 use async_trait::async_trait;
-use crds::{ServerReference, SftpgoFolder};
+use crds::{FieldManagement, ServerReference, SftpgoFolder, SftpgoFolderResourceStatus};
+use kube::ResourceExt;
 use sftpgo_client::folders::{FolderRequest, FolderResponse};
 
 #[async_trait]
@@ -16,8 +19,8 @@ impl SftpgoResource for SftpgoFolder {
 
     async fn get_request(
         &self,
-        _context: &ContextData,
-        _namespace: &String,
+        context: &ContextData,
+        namespace: &str,
     ) -> Result<Self::Request, Error> {
         let folder_configuration = &self.spec.configuration;
 
@@ -25,7 +28,12 @@ impl SftpgoResource for SftpgoFolder {
             name: folder_configuration.name.clone(),
             description: folder_configuration.description.clone(),
             mapped_path: folder_configuration.mapped_path.clone(),
-            filesystem: calculate_file_system(Some(&folder_configuration.filesystem)).await?,
+            filesystem: calculate_file_system(
+                Some(&folder_configuration.filesystem),
+                context,
+                namespace,
+            )
+            .await?,
         };
 
         Ok(request)
@@ -34,4 +42,30 @@ impl SftpgoResource for SftpgoFolder {
     fn get_server_reference(&self) -> &ServerReference {
         &self.spec.server_reference
     }
+
+    fn get_field_management(&self) -> FieldManagement {
+        self.spec.field_management.unwrap_or_default()
+    }
+
+    fn validate_update(
+        &self,
+        existing: &Self::Response,
+        request: &Self::Request,
+    ) -> Result<(), Error> {
+        check_passphrase_change(
+            &request.name,
+            self.status
+                .as_ref()
+                .and_then(|s| s.passphrase_hash.as_deref()),
+            &request.filesystem,
+            &self.uid().unwrap_or_default(),
+            self.spec.allow_passphrase_change.unwrap_or(false),
+            has_stored_files(existing.used_quota_size, existing.used_quota_files),
+        )
+    }
+
+    fn update_status(&self, request: &Self::Request, status: &mut SftpgoFolderResourceStatus) {
+        status.passphrase_hash =
+            get_passphrase_hash(&request.filesystem, &self.uid().unwrap_or_default());
+    }
 }
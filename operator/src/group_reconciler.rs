@@ -0,0 +1,80 @@
+use crate::filesystem::calculate_file_system;
+use crate::reconciler::{ContextData, SftpgoResource};
+use crate::user_reconciler::{calculate_permissions, get_virtual_folder_reference};
+use crate::Error;
+use async_trait::async_trait;
+use crds::{FieldManagement, ServerReference, SftpgoGroup};
+use sftpgo_client::groups::{GroupRequest, GroupResponse, GroupUserSettings};
+
+#[async_trait]
+impl SftpgoResource for SftpgoGroup {
+    type Request = GroupRequest;
+    type Response = GroupResponse;
+
+    fn get_name(&self) -> &str {
+        &self.spec.configuration.name
+    }
+
+    async fn get_request(
+        &self,
+        context: &ContextData,
+        namespace: &str,
+    ) -> Result<Self::Request, Error> {
+        let group_configuration = &self.spec.configuration;
+
+        let user_settings = if let Some(settings) = &group_configuration.user_settings {
+            let permissions = settings.global_permissions.as_ref().map(|global| {
+                calculate_permissions(global, settings.per_directory_permissions.as_ref())
+            });
+
+            let filesystem = if settings.filesystem.is_some() {
+                Some(calculate_file_system(settings.filesystem.as_ref(), context, namespace).await?)
+            } else {
+                None
+            };
+
+            Some(GroupUserSettings {
+                home_dir: settings.home_dir.clone(),
+                max_sessions: settings.max_sessions,
+                quota_size: settings.quota_size,
+                quota_files: settings.quota_files,
+                permissions,
+                upload_bandwidth: settings.upload_bandwidth,
+                download_bandwidth: settings.download_bandwidth,
+                filesystem,
+            })
+        } else {
+            None
+        };
+
+        let virtual_folders = if let Some(folders) = &group_configuration.virtual_folders {
+            let mut references = Vec::new();
+
+            for folder in folders {
+                let reference = get_virtual_folder_reference(namespace, folder, context).await?;
+                references.push(reference);
+            }
+
+            Some(references)
+        } else {
+            None
+        };
+
+        let request = GroupRequest {
+            name: group_configuration.name.clone(),
+            description: group_configuration.description.clone(),
+            user_settings,
+            virtual_folders,
+        };
+
+        Ok(request)
+    }
+
+    fn get_server_reference(&self) -> &ServerReference {
+        &self.spec.server_reference
+    }
+
+    fn get_field_management(&self) -> FieldManagement {
+        self.spec.field_management.unwrap_or_default()
+    }
+}
@@ -0,0 +1,219 @@
+use crate::conditions::set_reconcile_succeeded;
+use crate::default;
+use crate::events::publish_event;
+use crate::finalizers::{ensure_finalizer, remove_finalizer};
+use crate::reconciler::{ContextData, Error};
+use crate::sftpgo_multi_client::get_authorized_client;
+use crds::{IpListProtocol, SftpgoIpListEntry, SftpgoIpListMode, SftpgoIpListType};
+use kube::api::Patch;
+use kube::runtime::controller::Action;
+use kube::runtime::events::EventType;
+use kube::{Api, Resource, ResourceExt};
+use sftpgo_client::ip_lists::{
+    IpListClient, IpListEntryRequest, IpListEntryResponse, IpListMode, IpListType,
+    IP_LIST_PROTOCOL_FTP, IP_LIST_PROTOCOL_HTTP, IP_LIST_PROTOCOL_SSH, IP_LIST_PROTOCOL_WEBDAV,
+};
+use std::sync::Arc;
+
+pub async fn reconcile_sftpgo_ip_list_entry(
+    resource: Arc<SftpgoIpListEntry>,
+    context: Arc<ContextData>,
+) -> Result<Action, Error> {
+    let name = resource.name_any();
+
+    let namespace = resource.namespace().ok_or(Error::UserInput(
+        "Expected SftpgoIpListEntry resource to be namespaced. Can't deploy to unknown namespace."
+            .to_string(),
+    ))?;
+
+    let resource_api: Api<SftpgoIpListEntry> =
+        Api::namespaced(context.kubernetes_client.clone(), &namespace);
+
+    let mut resource = resource_api.get(&name).await?;
+
+    let api_client =
+        get_authorized_client(&resource.spec.server_reference, &context, &namespace).await?;
+
+    let last_entry = resource
+        .status
+        .as_ref()
+        .and_then(|s| s.list_type.zip(s.ip_or_net.clone()));
+
+    if resource.meta().deletion_timestamp.is_some() {
+        info!("Ip list entry {namespace}/{name} is being deleted, cleaning up");
+
+        if let Some((list_type, ip_or_net)) = &last_entry {
+            api_client
+                .delete_ip_list_entry(map_list_type(*list_type), ip_or_net)
+                .await?;
+            info!("Deleted ip list entry {} from SFTPGo", ip_or_net);
+
+            publish_event(
+                &context,
+                &resource,
+                EventType::Normal,
+                "Deleted",
+                "Delete",
+                format!("Deleted {} from SFTPGo", ip_or_net),
+            )
+            .await;
+        }
+
+        remove_finalizer::<SftpgoIpListEntry>(context.kubernetes_client.clone(), &name, &namespace)
+            .await?;
+
+        info!("Removed finalizer");
+
+        publish_event(
+            &context,
+            &resource,
+            EventType::Normal,
+            "FinalizerRemoved",
+            "Delete",
+            "Removed the finalizer, the resource can be deleted".to_string(),
+        )
+        .await;
+
+        return Ok(Action::await_change());
+    }
+
+    resource = ensure_finalizer(resource, context.kubernetes_client.clone()).await?;
+
+    let configuration = &resource.spec.configuration;
+    let list_type = map_list_type(configuration.list_type);
+    let request = get_request(&resource)?;
+
+    // The list type and network identify the entry, so changing either means replacing it
+    if let Some((last_list_type, last_ip_or_net)) = &last_entry {
+        if *last_list_type != configuration.list_type || *last_ip_or_net != request.ipornet {
+            info!(
+                "Ip list entry {namespace}/{name} changed from {} to {}, removing the old entry",
+                last_ip_or_net, request.ipornet
+            );
+
+            api_client
+                .delete_ip_list_entry(map_list_type(*last_list_type), last_ip_or_net)
+                .await?;
+
+            publish_event(
+                &context,
+                &resource,
+                EventType::Normal,
+                "Deleted",
+                "Update",
+                format!("Deleted the previous entry {} from SFTPGo", last_ip_or_net),
+            )
+            .await;
+        }
+    }
+
+    let existing_entry = api_client
+        .get_ip_list_entry(list_type, &request.ipornet)
+        .await?;
+
+    if let Some(existing_entry) = existing_entry {
+        if entry_matches_request(&existing_entry, &request) {
+            debug!("Ip list entry {} is in sync with SFTPGo", request.ipornet);
+        } else {
+            info!("Updating ip list entry {}", request.ipornet);
+
+            api_client.update_ip_list_entry(list_type, &request).await?;
+
+            info!("Updated ip list entry {}", request.ipornet);
+
+            publish_event(
+                &context,
+                &resource,
+                EventType::Normal,
+                "Updated",
+                "Update",
+                format!("Updated {} in SFTPGo", request.ipornet),
+            )
+            .await;
+        }
+    } else {
+        info!("Creating ip list entry {}", request.ipornet);
+
+        api_client.create_ip_list_entry(list_type, &request).await?;
+
+        info!("Created ip list entry {}", request.ipornet);
+
+        publish_event(
+            &context,
+            &resource,
+            EventType::Normal,
+            "Created",
+            "Create",
+            format!("Created {} in SFTPGo", request.ipornet),
+        )
+        .await;
+    }
+
+    let mut s = resource.status.clone().unwrap_or_default();
+    s.list_type = Some(configuration.list_type);
+    s.ip_or_net = Some(request.ipornet);
+    set_reconcile_succeeded(&mut s, resource.metadata.generation);
+
+    if resource.status.as_ref() != Some(&s) {
+        resource.status = Some(s);
+
+        resource_api
+            .patch_status(&name, &default(), &Patch::Merge(resource))
+            .await?;
+
+        info!("Updated status for ip list entry {namespace}/{name}");
+    }
+
+    Ok(Action::await_change())
+}
+
+fn entry_matches_request(existing: &IpListEntryResponse, request: &IpListEntryRequest) -> bool {
+    existing.description.as_deref().unwrap_or_default()
+        == request.description.as_deref().unwrap_or_default()
+        && existing.mode == request.mode
+        && existing.protocols == request.protocols
+}
+
+fn map_list_type(list_type: SftpgoIpListType) -> IpListType {
+    match list_type {
+        SftpgoIpListType::Allowlist => IpListType::Allowlist,
+        SftpgoIpListType::Defender => IpListType::Defender,
+        SftpgoIpListType::RateLimiterSafeList => IpListType::RateLimiterSafeList,
+    }
+}
+
+fn get_request(resource: &SftpgoIpListEntry) -> Result<IpListEntryRequest, Error> {
+    let configuration = &resource.spec.configuration;
+
+    let mode = match configuration.mode.unwrap_or_default() {
+        SftpgoIpListMode::Allow => IpListMode::Allow,
+        SftpgoIpListMode::Deny => {
+            if configuration.list_type != SftpgoIpListType::Defender {
+                return Err(Error::UserInput(
+                    "The Deny mode is only valid for entries in the Defender list".to_string(),
+                ));
+            }
+
+            IpListMode::Deny
+        }
+    };
+
+    let protocols = configuration
+        .protocols
+        .iter()
+        .flatten()
+        .map(|p| match p {
+            IpListProtocol::Ssh => IP_LIST_PROTOCOL_SSH,
+            IpListProtocol::Ftp => IP_LIST_PROTOCOL_FTP,
+            IpListProtocol::WebDav => IP_LIST_PROTOCOL_WEBDAV,
+            IpListProtocol::Http => IP_LIST_PROTOCOL_HTTP,
+        })
+        .fold(0, |acc, p| acc | p);
+
+    Ok(IpListEntryRequest {
+        ipornet: configuration.ip_or_net.clone(),
+        description: configuration.description.clone(),
+        mode,
+        protocols,
+    })
+}
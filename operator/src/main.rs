@@ -1,30 +1,57 @@
 mod admin_reconciler;
+mod api_key_reconciler;
+mod conditions;
 mod consts;
+mod dependency_watchers;
+mod drift;
+mod event_action_reconciler;
+mod event_rule_reconciler;
+mod events;
 mod filesystem;
 mod finalizers;
 mod folder_reconciler;
+mod group_reconciler;
+mod ip_list_entry_reconciler;
+mod merge;
+mod metrics;
 mod reconciler;
+mod role_reconciler;
+mod secret_hash;
 mod sftpgo_multi_client;
 mod sftpgo_server_reconciler;
+mod share_reconciler;
+mod user_binding;
 mod user_reconciler;
+mod value_from;
 mod viper_environment_serializer;
 
 extern crate pretty_env_logger;
 #[macro_use]
 extern crate log;
 
+use crate::api_key_reconciler::reconcile_sftpgo_api_key;
+use crate::dependency_watchers::{
+    map_object, map_value_source_config_map, map_value_source_secret, watch_admins_for_role,
+    watch_event_rules_for_action, watch_shares_for_user, watch_users_for_folder,
+    watch_users_for_group, watch_users_for_role, SharedMetadataWatch,
+};
+use crate::ip_list_entry_reconciler::reconcile_sftpgo_ip_list_entry;
+use crate::metrics::serve_metrics;
 pub use crate::reconciler::Error;
-use crate::reconciler::{make_reconciler, sftpgo_api_resource_reconciler, ContextData};
+use crate::reconciler::{
+    make_reconciler, make_reconciler_without_status, sftpgo_api_resource_reconciler, ContextData,
+};
 use crate::sftpgo_server_reconciler::reconcile_sftpgo_server;
-use crds::{SftpgoAdmin, SftpgoFolder, SftpgoUser};
-use futures::{stream, Stream, StreamExt, TryStreamExt};
+use crate::share_reconciler::reconcile_sftpgo_share;
+use crds::{
+    SftpgoAdmin, SftpgoEventAction, SftpgoEventRule, SftpgoFolder, SftpgoGroup, SftpgoRole,
+    SftpgoUser,
+};
 use k8s_openapi::api::apps::v1::Deployment;
-use k8s_openapi::api::core::v1::{Secret, Service};
+use k8s_openapi::api::core::v1::{ConfigMap, Secret, Service};
 use kube::client::Client;
 use kube::runtime::watcher;
-use kube::{Api, ResourceExt};
-use kube_runtime::reflector::ObjectRef;
-use kube_runtime::WatchStreamExt;
+use kube::Api;
 use tokio::task::{JoinError, JoinSet};
 
 pub fn default<T: Default>() -> T {
@@ -46,8 +73,10 @@ async fn main() -> Result<(), JoinError> {
     let deployments_api: Api<Deployment> = Api::all(kubernetes_client.clone());
     let secrets_api: Api<Secret> = Api::all(kubernetes_client.clone());
     let services_api: Api<Service> = Api::all(kubernetes_client.clone());
+    let api_key_secrets_api: Api<Secret> = Api::all(kubernetes_client.clone());
+    let user_binding_secrets_api: Api<Secret> = Api::all(kubernetes_client.clone());
 
-    reconcilers.spawn(make_reconciler(
+    reconcilers.spawn(make_reconciler_without_status(
         kubernetes_client.clone(),
         reconcile_sftpgo_server,
         |c| {
@@ -59,96 +88,131 @@ async fn main() -> Result<(), JoinError> {
         },
     ));
 
+    // Users, folders, groups and event actions read values from secrets and config maps, which are
+    // watched once for all of them
+    let value_source_secrets = SharedMetadataWatch::<Secret>::new();
+    let value_source_config_maps = SharedMetadataWatch::<ConfigMap>::new();
+
     let user_folder_trigger = watch_users_for_folder(kubernetes_client.clone());
+    let user_group_trigger = watch_users_for_group(kubernetes_client.clone());
+    let user_role_trigger = watch_users_for_role(kubernetes_client.clone());
+    let user_secret_trigger = value_source_secrets.subscribe();
+    let user_config_map_trigger = value_source_config_maps.subscribe();
 
     reconcilers.spawn(make_reconciler(
         kubernetes_client.clone(),
         sftpgo_api_resource_reconciler::<SftpgoUser>,
-        |c| c.watches_stream(user_folder_trigger, map_user),
+        |c| {
+            let store = c.store();
+            c.watches_stream(user_folder_trigger, map_object)
+                .watches_stream(user_group_trigger, map_object)
+                .watches_stream(user_role_trigger, map_object)
+                .watches_stream(user_secret_trigger, map_value_source_secret(store.clone()))
+                .watches_stream(user_config_map_trigger, map_value_source_config_map(store))
+                .owns(user_binding_secrets_api, default())
+        },
     ));
+
+    let folder_secret_trigger = value_source_secrets.subscribe();
+    let folder_config_map_trigger = value_source_config_maps.subscribe();
+
     reconcilers.spawn(make_reconciler(
         kubernetes_client.clone(),
         sftpgo_api_resource_reconciler::<SftpgoFolder>,
-        |c| c,
+        |c| {
+            let store = c.store();
+            c.watches_stream(
+                folder_secret_trigger,
+                map_value_source_secret(store.clone()),
+            )
+            .watches_stream(
+                folder_config_map_trigger,
+                map_value_source_config_map(store),
+            )
+        },
     ));
+
+    let admin_role_trigger = watch_admins_for_role(kubernetes_client.clone());
+
     reconcilers.spawn(make_reconciler(
         kubernetes_client.clone(),
         sftpgo_api_resource_reconciler::<SftpgoAdmin>,
+        |c| c.watches_stream(admin_role_trigger, map_object),
+    ));
+
+    let group_secret_trigger = value_source_secrets.subscribe();
+    let group_config_map_trigger = value_source_config_maps.subscribe();
+
+    reconcilers.spawn(make_reconciler(
+        kubernetes_client.clone(),
+        sftpgo_api_resource_reconciler::<SftpgoGroup>,
+        |c| {
+            let store = c.store();
+            c.watches_stream(group_secret_trigger, map_value_source_secret(store.clone()))
+                .watches_stream(group_config_map_trigger, map_value_source_config_map(store))
+        },
+    ));
+    reconcilers.spawn(make_reconciler(
+        kubernetes_client.clone(),
+        sftpgo_api_resource_reconciler::<SftpgoRole>,
+        |c| c,
+    ));
+    reconcilers.spawn(make_reconciler(
+        kubernetes_client.clone(),
+        reconcile_sftpgo_api_key,
+        |c| c.owns(api_key_secrets_api, default()),
+    ));
+
+    let event_action_secret_trigger = value_source_secrets.subscribe();
+    let event_action_config_map_trigger = value_source_config_maps.subscribe();
+
+    reconcilers.spawn(make_reconciler(
+        kubernetes_client.clone(),
+        sftpgo_api_resource_reconciler::<SftpgoEventAction>,
+        |c| {
+            let store = c.store();
+            c.watches_stream(
+                event_action_secret_trigger,
+                map_value_source_secret(store.clone()),
+            )
+            .watches_stream(
+                event_action_config_map_trigger,
+                map_value_source_config_map(store),
+            )
+        },
+    ));
+
+    let event_rule_action_trigger = watch_event_rules_for_action(kubernetes_client.clone());
+
+    reconcilers.spawn(make_reconciler(
+        kubernetes_client.clone(),
+        sftpgo_api_resource_reconciler::<SftpgoEventRule>,
+        |c| c.watches_stream(event_rule_action_trigger, map_object),
+    ));
+
+    let share_user_trigger = watch_shares_for_user(kubernetes_client.clone());
+
+    reconcilers.spawn(make_reconciler(
+        kubernetes_client.clone(),
+        reconcile_sftpgo_share,
+        |c| c.watches_stream(share_user_trigger, map_object),
+    ));
+    reconcilers.spawn(make_reconciler(
+        kubernetes_client.clone(),
+        reconcile_sftpgo_ip_list_entry,
         |c| c,
     ));
 
     info!("Reconcilers spawned");
 
+    reconcilers.spawn(value_source_secrets.run(kubernetes_client.clone()));
+    reconcilers.spawn(value_source_config_maps.run(kubernetes_client.clone()));
+
+    reconcilers.spawn(serve_metrics());
+
     while let Some(res) = reconcilers.join_next().await {
         res?;
     }
 
     Ok(())
 }
-
-fn map_user(u: SftpgoUser) -> Option<ObjectRef<SftpgoUser>> {
-    Some(ObjectRef::from_obj(&u))
-}
-
-fn watch_users_for_folder(
-    kubernetes_client: Client,
-) -> impl Stream<Item = Result<SftpgoUser, watcher::Error>> + Send + Sized + 'static {
-    let folders_api: Api<SftpgoFolder> = Api::all(kubernetes_client);
-
-    watcher(folders_api, default())
-        .applied_objects()
-        .and_then(list_users)
-        .flat_map_unordered(None, |users| {
-            stream::iter(users.into_iter().flatten().map(Ok))
-        })
-}
-
-async fn list_users(folder: SftpgoFolder) -> Result<Vec<SftpgoUser>, watcher::Error> {
-    info!("Getting user list");
-    let kubernetes_client = Client::try_default()
-        .await
-        .expect("Expected a valid KUBECONFIG environment variable.");
-
-    let api: Api<SftpgoUser> = Api::all(kubernetes_client);
-    let response = api.list(&default()).await.map_err(|e| {
-        watcher::Error::WatchError(kube::core::ErrorResponse {
-            code: 0,
-            message: e.to_string(),
-            reason: "".to_string(),
-            status: "".to_string(),
-        })
-    })?;
-
-    let folder_name = folder.name_any();
-    let folder_namespace = &folder.metadata.namespace.unwrap_or("".to_string());
-
-    let items = response
-        .items
-        .into_iter()
-        .filter(|u| {
-            if let Some(user_ns) = &u.metadata.namespace {
-                u.spec
-                    .configuration
-                    .virtual_folders
-                    .as_ref()
-                    .is_some_and(|ve| {
-                        ve.iter().any(|v| {
-                            if v.name != folder_name {
-                                return false;
-                            }
-
-                            if let Some(ns) = &v.namespace {
-                                ns == folder_namespace
-                            } else {
-                                user_ns == folder_namespace
-                            }
-                        })
-                    })
-            } else {
-                false
-            }
-        })
-        .collect();
-
-    Ok(items)
-}
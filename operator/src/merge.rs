@@ -0,0 +1,108 @@
+use serde_json::Value;
+
+/// Overlays the fields set in the request on the resource as SFTPGo returned it, so fields the
+/// request leaves out keep the value they have on the server. This works on the raw JSON, as the
+/// response types don't model every field SFTPGo has. Lists are replaced as a whole.
+pub fn merge_json(mut existing: Value, request: Value) -> Value {
+    overlay(&mut existing, request);
+
+    existing
+}
+
+fn overlay(target: &mut Value, value: Value) {
+    match (target, value) {
+        (_, Value::Null) => {}
+        (Value::Object(target), Value::Object(values)) => {
+            for (key, value) in values {
+                overlay(target.entry(key).or_insert(Value::Null), value);
+            }
+        }
+        (target, value) => *target = value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use sftpgo_client::{UserFilters, UserRequest, UserResponse, UserStatus};
+
+    #[test]
+    fn keeps_fields_not_set_in_the_request() {
+        let request = UserRequest {
+            status: None,
+            username: "test".to_string(),
+            email: None,
+            quota_size: Some(100),
+            filters: Some(UserFilters {
+                allowed_ip: Some(vec!["10.0.0.0/8".to_string()]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let existing = json!({
+            "id": 1,
+            "status": 0,
+            "username": "test",
+            "email": "support@example.com",
+            "home_dir": "/srv/sftpgo/data/test",
+            "quota_size": 50,
+            "additional_info": "managed in the web admin",
+            "filters": {
+                "allowed_ip": ["0.0.0.0/0"],
+                "denied_ip": ["10.0.0.1/32"],
+                "web_client": ["write-disabled"],
+                "allow_api_key_auth": true,
+                "hooks": {"external_auth_disabled": true},
+            },
+        });
+
+        let merged = merge_json(existing, serde_json::to_value(&request).unwrap());
+
+        assert_eq!(merged["status"], 0);
+        assert_eq!(merged["email"], "support@example.com");
+        assert_eq!(merged["quota_size"], 100);
+        assert_eq!(merged["additional_info"], "managed in the web admin");
+        assert_eq!(merged["filters"]["allowed_ip"], json!(["10.0.0.0/8"]));
+        assert_eq!(merged["filters"]["denied_ip"], json!(["10.0.0.1/32"]));
+        assert_eq!(merged["filters"]["web_client"], json!(["write-disabled"]));
+        assert_eq!(merged["filters"]["allow_api_key_auth"], true);
+        assert_eq!(
+            merged["filters"]["hooks"],
+            json!({"external_auth_disabled": true})
+        );
+
+        let response: UserResponse = serde_json::from_value(merged).unwrap();
+        assert_eq!(response.status, UserStatus::Disabled);
+        assert_eq!(response.quota_size, Some(100));
+    }
+
+    #[test]
+    fn keeps_filesystem_not_set_in_the_request() {
+        let request = UserRequest {
+            username: "test".to_string(),
+            filesystem: None,
+            ..Default::default()
+        };
+        let existing = json!({
+            "id": 1,
+            "status": 1,
+            "username": "test",
+            "home_dir": "/srv/sftpgo/data/test",
+            "filesystem": {
+                "provider": 1,
+                "s3config": {"bucket": "uploads", "region": "eu-west-1"},
+            },
+        });
+
+        let merged = merge_json(existing, serde_json::to_value(&request).unwrap());
+
+        assert_eq!(
+            merged["filesystem"],
+            json!({
+                "provider": 1,
+                "s3config": {"bucket": "uploads", "region": "eu-west-1"},
+            })
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server, StatusCode};
+use prometheus::{Encoder, IntCounterVec, Opts, TextEncoder};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::OnceLock;
+
+const METRICS_PORT: u16 = 9090;
+
+/// Counts the fields changed outside of the operator, that were set back to the requested value
+pub fn drift_corrections() -> &'static IntCounterVec {
+    static DRIFT_CORRECTIONS: OnceLock<IntCounterVec> = OnceLock::new();
+
+    DRIFT_CORRECTIONS.get_or_init(|| {
+        let counter = IntCounterVec::new(
+            Opts::new(
+                "sftpgo_operator_drift_corrections_total",
+                "Fields changed in SFTPGo outside of the operator, and set back by it",
+            ),
+            &["kind", "field"],
+        )
+        .expect("Metric options are valid");
+
+        prometheus::register(Box::new(counter.clone())).expect("Metric is only registered once");
+
+        counter
+    })
+}
+
+/// Serves the metrics in the prometheus format on `/metrics`
+pub async fn serve_metrics() {
+    let address = SocketAddr::from(([0, 0, 0, 0], METRICS_PORT));
+
+    let make_service =
+        make_service_fn(|_| async { Ok::<_, Infallible>(service_fn(handle_request)) });
+
+    let server = match Server::try_bind(&address) {
+        Ok(builder) => builder.serve(make_service),
+        Err(e) => {
+            error!("Failed to serve metrics on {}: {}", address, e);
+            return;
+        }
+    };
+
+    // Registers the metrics, so they're known before anything is counted
+    drift_corrections();
+
+    info!("Serving metrics on {}", address);
+
+    if let Err(e) = server.await {
+        error!("Metrics server failed: {}", e);
+    }
+}
+
+async fn handle_request(request: Request<Body>) -> Result<Response<Body>, Infallible> {
+    if request.uri().path() != "/metrics" {
+        let mut response = Response::new(Body::empty());
+        *response.status_mut() = StatusCode::NOT_FOUND;
+        return Ok(response);
+    }
+
+    let mut buffer = vec![];
+    let encoder = TextEncoder::new();
+
+    if let Err(e) = encoder.encode(&prometheus::gather(), &mut buffer) {
+        error!("Failed to encode metrics: {}", e);
+    }
+
+    let mut response = Response::new(Body::from(buffer));
+    response.headers_mut().insert(
+        hyper::header::CONTENT_TYPE,
+        hyper::header::HeaderValue::from_static("text/plain; version=0.0.4"),
+    );
+
+    Ok(response)
+}
@@ -1,33 +1,77 @@
+use crate::conditions::{
+    set_condition, set_reconcile_failed, set_reconcile_succeeded, ReconcileFailure,
+    CONDITION_DRIFTED,
+};
 use crate::default;
+use crate::drift::{find_drift, top_level_field};
+use crate::events::{get_reporter, publish_event};
 use crate::finalizers::{ensure_finalizer, remove_finalizer};
-use crate::sftpgo_multi_client::{get_api_client, SftpgoMultiClient};
+use crate::merge::merge_json;
+use crate::metrics::drift_corrections;
+use crate::secret_hash::hash_request;
+use crate::sftpgo_multi_client::{get_api_client, OperatorSftpgoClient, SftpgoMultiClient};
 use crate::viper_environment_serializer::ViperEnvironmentSerializerError;
 use async_trait::async_trait;
-use crds::{ServerReference, SftpgoStatus};
+use crds::{FieldManagement, ReconcileStatus, RequestStatus, ServerReference, SftpgoStatus};
 use futures::stream::StreamExt;
 use futures::TryFuture;
 use k8s_openapi::NamespaceResourceScope;
 use kube::api::Patch;
 use kube::core::object::HasStatus;
 use kube::runtime::controller::Action;
+use kube::runtime::events::{EventType, Reporter};
 use kube::runtime::watcher::Config;
 use kube::runtime::Controller;
 use kube::{Api, Client, CustomResourceExt, Resource, ResourceExt};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use sftpgo_client::{
-    AuthorizedSftpgoClient, CreatedFrom, Creates, EasyRestSftpgoClient, Existing,
-    RefreshableAdminAuthContext, SftpgoClient,
-};
+use serde_json::json;
+use sftpgo_client::{CreatedFrom, Creates, EasyRestSftpgoClient, Existing};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fmt::Debug;
 use std::hash::Hash;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, PoisonError};
 use std::time::Duration;
 
+const RESYNC_INTERVAL_ENV: &str = "RESYNC_INTERVAL_SECONDS";
+const DEFAULT_RESYNC_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Runs a reconciler for resources reporting their state in a status implementing
+/// `ReconcileStatus`. Failures are recorded in the status by the error policy.
 pub async fn make_reconciler<TResource, ReconcilerFut, ReconcilerFn, CustomizeFn>(
     kubernetes_client: Client,
     recon: ReconcilerFn,
     customize_controller: CustomizeFn,
+) where
+    TResource: Clone
+        + Resource<Scope = NamespaceResourceScope>
+        + HasStatus
+        + CustomResourceExt
+        + DeserializeOwned
+        + Debug
+        + Send
+        + Sync
+        + 'static,
+    TResource::DynamicType: Debug + Unpin + Eq + Hash + Clone + Default,
+    TResource::Status: ReconcileStatus + Default + Clone + PartialEq + Serialize + Send,
+    ReconcilerFn: FnMut(Arc<TResource>, Arc<ContextData>) -> ReconcilerFut,
+    ReconcilerFut: TryFuture<Ok = Action, Error = Error> + Send + 'static,
+    CustomizeFn: FnOnce(Controller<TResource>) -> Controller<TResource>,
+{
+    run_controller(
+        kubernetes_client,
+        recon,
+        customize_controller,
+        error_policy::<TResource>,
+    )
+    .await
+}
+
+/// Runs a reconciler for resources without a status. Failures are only logged.
+pub async fn make_reconciler_without_status<TResource, ReconcilerFut, ReconcilerFn, CustomizeFn>(
+    kubernetes_client: Client,
+    recon: ReconcilerFn,
+    customize_controller: CustomizeFn,
 ) where
     TResource:
         Clone + Resource + CustomResourceExt + DeserializeOwned + Debug + Send + Sync + 'static,
@@ -35,6 +79,29 @@ pub async fn make_reconciler<TResource, ReconcilerFut, ReconcilerFn, CustomizeFn
     ReconcilerFn: FnMut(Arc<TResource>, Arc<ContextData>) -> ReconcilerFut,
     ReconcilerFut: TryFuture<Ok = Action, Error = Error> + Send + 'static,
     CustomizeFn: FnOnce(Controller<TResource>) -> Controller<TResource>,
+{
+    run_controller(
+        kubernetes_client,
+        recon,
+        customize_controller,
+        log_error_policy::<TResource>,
+    )
+    .await
+}
+
+async fn run_controller<TResource, ReconcilerFut, ReconcilerFn, CustomizeFn, ErrorPolicyFn>(
+    kubernetes_client: Client,
+    recon: ReconcilerFn,
+    customize_controller: CustomizeFn,
+    error_policy: ErrorPolicyFn,
+) where
+    TResource:
+        Clone + Resource + CustomResourceExt + DeserializeOwned + Debug + Send + Sync + 'static,
+    TResource::DynamicType: Debug + Unpin + Eq + Hash + Clone + Default,
+    ReconcilerFn: FnMut(Arc<TResource>, Arc<ContextData>) -> ReconcilerFut,
+    ReconcilerFut: TryFuture<Ok = Action, Error = Error> + Send + 'static,
+    CustomizeFn: FnOnce(Controller<TResource>) -> Controller<TResource>,
+    ErrorPolicyFn: Fn(Arc<TResource>, &Error, Arc<ContextData>) -> Action,
 {
     info!("Starting reconciler for {:#?}", TResource::crd_name());
 
@@ -42,6 +109,9 @@ pub async fn make_reconciler<TResource, ReconcilerFut, ReconcilerFn, CustomizeFn
     let context = Arc::new(ContextData {
         kubernetes_client: kubernetes_client.clone(),
         sftpgo_client: SftpgoMultiClient::new(),
+        reporter: get_reporter(),
+        resync_interval: get_resync_interval(),
+        reported_failures: default(),
     });
 
     let mut controller_setup: Controller<TResource> =
@@ -50,37 +120,242 @@ pub async fn make_reconciler<TResource, ReconcilerFut, ReconcilerFn, CustomizeFn
     controller_setup = customize_controller(controller_setup);
 
     controller_setup
-        .run(recon, error_policy, context)
-        .for_each(|res| async move {
-            match res {
-                Ok(o) => debug!("reconciled: {:?}", o),
-                Err(e) => error!("reconcile failed: {:?}", e),
+        .run(recon, error_policy, context.clone())
+        .for_each(|res| {
+            let context = context.clone();
+            async move {
+                match res {
+                    Ok(o) => {
+                        debug!("reconciled: {:?}", o);
+                        context.forget_failure(&failure_key(&o.0.name, o.0.namespace.as_deref()));
+                    }
+                    Err(e) => error!("reconcile failed: {:?}", e),
+                }
             }
         })
         .await
 }
 
-fn error_policy<TResource>(
-    echo: Arc<TResource>,
-    error: &Error,
-    _context: Arc<ContextData>,
-) -> Action
+fn failure_key(name: &str, namespace: Option<&str>) -> String {
+    format!("{}/{}", namespace.unwrap_or_default(), name)
+}
+
+fn log_error<TResource>(resource: &TResource, error: &Error)
 where
-    TResource:
-        Clone + Resource + CustomResourceExt + DeserializeOwned + Debug + Send + Sync + 'static,
+    TResource: CustomResourceExt + Debug,
 {
     error!(
         "Reconciliation error while reconciling type {}:\n{:?}.\n{:?}",
         TResource::crd_name(),
         error,
-        echo
+        resource
     );
+}
+
+fn log_error_policy<TResource>(
+    echo: Arc<TResource>,
+    error: &Error,
+    context: Arc<ContextData>,
+) -> Action
+where
+    TResource:
+        Clone + Resource + CustomResourceExt + DeserializeOwned + Debug + Send + Sync + 'static,
+    TResource::DynamicType: Default,
+{
+    log_error(echo.as_ref(), error);
+
+    let failure = ReconcileFailure::from(error);
+
+    // These resources have no status to record the failure in, so the context remembers which
+    // failure was published, to not publish it again on every retry
+    if !context.remember_failure(
+        failure_key(&echo.name_any(), echo.namespace().as_deref()),
+        &failure.message,
+    ) {
+        return Action::requeue(Duration::from_secs(15));
+    }
+
+    // Events can't be published synchronously, so it's done in the background
+    tokio::spawn(async move {
+        publish_event(
+            &context,
+            echo.as_ref(),
+            EventType::Warning,
+            failure.reason,
+            "Reconcile",
+            failure.message,
+        )
+        .await;
+    });
+
     Action::requeue(Duration::from_secs(15))
 }
 
+fn error_policy<TResource>(echo: Arc<TResource>, error: &Error, context: Arc<ContextData>) -> Action
+where
+    TResource: Clone
+        + Resource<Scope = NamespaceResourceScope>
+        + HasStatus
+        + CustomResourceExt
+        + DeserializeOwned
+        + Debug
+        + Send
+        + Sync
+        + 'static,
+    TResource::DynamicType: Default,
+    TResource::Status: ReconcileStatus + Default + Clone + PartialEq + Serialize + Send,
+{
+    log_error(echo.as_ref(), error);
+
+    let name = echo.name_any();
+    let namespace = echo.namespace().unwrap_or_default();
+    let failure = ReconcileFailure::from(error);
+
+    // The error policy can't wait for the status to be patched, so it's done in the background
+    tokio::spawn(async move {
+        if let Err(e) = record_failure::<TResource>(&context, &name, &namespace, failure).await {
+            warn!("Failed to record the error in the status of {namespace}/{name}: {e}");
+        }
+    });
+
+    Action::requeue(Duration::from_secs(15))
+}
+
+async fn record_failure<TResource>(
+    context: &ContextData,
+    name: &str,
+    namespace: &str,
+    failure: ReconcileFailure,
+) -> Result<(), Error>
+where
+    TResource:
+        Clone + Resource<Scope = NamespaceResourceScope> + HasStatus + DeserializeOwned + Debug,
+    TResource::DynamicType: Default,
+    TResource::Status: ReconcileStatus + Default + Clone + PartialEq + Serialize,
+{
+    let resource_api: Api<TResource> =
+        Api::namespaced(context.kubernetes_client.clone(), namespace);
+
+    let Some(resource) = resource_api.get_opt(name).await? else {
+        return Ok(());
+    };
+
+    let mut status = resource.status().cloned().unwrap_or_default();
+    // Failed reconciliations are retried every few seconds, so the event is only published
+    // when the failure differs from the one already recorded
+    let is_new_failure = status.get_last_error() != Some(failure.message.as_str());
+    set_reconcile_failed(&mut status, &failure, resource.meta().generation);
+
+    if resource.status() != Some(&status) {
+        resource_api
+            .patch_status(name, &default(), &Patch::Merge(failure_patch(&status)))
+            .await?;
+    }
+
+    if is_new_failure {
+        publish_event(
+            context,
+            &resource,
+            EventType::Warning,
+            failure.reason,
+            "Reconcile",
+            failure.message,
+        )
+        .await;
+    }
+
+    Ok(())
+}
+
+/// Only the reconciliation state is patched, so nothing else in the status is overwritten. If
+/// the resource has no status yet, this becomes its status, so it must be valid on its own.
+fn failure_patch<S: ReconcileStatus>(status: &S) -> serde_json::Value {
+    json!({ "status": status.reconcile_state() })
+}
+
+/// The top level fields of the request that changed since it was last sent to SFTPGo
+enum ChangedFields {
+    /// Without hashes of the previous request, any field might have changed
+    Unknown {
+        request_changed: bool,
+    },
+    Known(BTreeSet<String>),
+}
+
+impl ChangedFields {
+    fn is_changed(&self, field: &str) -> bool {
+        match self {
+            ChangedFields::Unknown { request_changed } => *request_changed,
+            ChangedFields::Known(fields) => fields.contains(field),
+        }
+    }
+}
+
+fn hash_fields(salt: &str, request: &serde_json::Value) -> BTreeMap<String, String> {
+    request
+        .as_object()
+        .into_iter()
+        .flatten()
+        .map(|(field, value)| (field.clone(), hash_request(salt, value)))
+        .collect()
+}
+
+fn find_changed_fields(
+    previous: Option<&BTreeMap<String, String>>,
+    current: &BTreeMap<String, String>,
+    request_changed: bool,
+) -> ChangedFields {
+    let Some(previous) = previous else {
+        return ChangedFields::Unknown { request_changed };
+    };
+
+    ChangedFields::Known(
+        previous
+            .keys()
+            .chain(current.keys())
+            .filter(|field| previous.get(*field) != current.get(*field))
+            .cloned()
+            .collect(),
+    )
+}
+
 pub struct ContextData {
     pub kubernetes_client: Client,
     pub sftpgo_client: SftpgoMultiClient,
+    pub reporter: Reporter,
+    /// How often resources are compared with SFTPGo, to undo changes made outside the operator
+    pub resync_interval: Duration,
+    /// The last failure published for resources without a status, by namespace and name
+    reported_failures: Mutex<HashMap<String, String>>,
+}
+
+impl ContextData {
+    /// Remembers the failure of a resource, and returns whether it differs from the last one
+    fn remember_failure(&self, key: String, message: &str) -> bool {
+        let mut failures = self
+            .reported_failures
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        failures.insert(key, message.to_string()).as_deref() != Some(message)
+    }
+
+    fn forget_failure(&self, key: &str) {
+        self.reported_failures
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(key);
+    }
+}
+
+fn get_resync_interval() -> Duration {
+    match std::env::var(RESYNC_INTERVAL_ENV) {
+        Ok(value) => value.parse().map(Duration::from_secs).unwrap_or_else(|e| {
+            warn!("Invalid {RESYNC_INTERVAL_ENV} {value}: {e}, using the default");
+            DEFAULT_RESYNC_INTERVAL
+        }),
+        Err(_) => DEFAULT_RESYNC_INTERVAL,
+    }
 }
 
 /// All errors possible to occur during reconciliation
@@ -117,22 +392,71 @@ pub enum Error {
 
     #[error("Error while decoding base64: {0}")]
     DecodeError(#[from] base64::DecodeError),
+
+    #[error("Error while converting between the request and the JSON of SFTPGo: {0}")]
+    SerializationFailed(#[from] serde_json::Error),
 }
 
 #[async_trait]
-pub trait SftpgoResource {
-    type Request: Serialize + Sync + Creates<Self::Response>;
-    type Response: for<'de> Deserialize<'de> + CreatedFrom<Self::Request>;
+pub trait SftpgoResource: HasStatus {
+    type Request: Serialize + DeserializeOwned + Sync + Creates<Self::Response>;
+    type Response: Serialize + for<'de> Deserialize<'de> + CreatedFrom<Self::Request>;
 
     fn get_name(&self) -> &str;
 
     async fn get_request(
         &self,
         context: &ContextData,
-        namespace: &String,
+        namespace: &str,
     ) -> Result<Self::Request, Error>;
 
     fn get_server_reference(&self) -> &ServerReference;
+
+    /// Whether existing resources are replaced with the request, or only the fields set in the
+    /// request are changed
+    fn get_field_management(&self) -> FieldManagement;
+
+    /// Checks that the resource on the server can be updated with the request. Changes that
+    /// would break the existing resource should be rejected here.
+    fn validate_update(
+        &self,
+        _existing: &Self::Response,
+        _request: &Self::Request,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Adjusts the request before an existing resource is updated, for example to leave out
+    /// values that can't be read back from the server and didn't change
+    fn prepare_update(&self, _request: &mut Self::Request) {}
+
+    /// Records what is sent to the server in the status. The status is only saved if the
+    /// resource was created or updated successfully
+    fn update_status(&self, _request: &Self::Request, _status: &mut Self::Status) {}
+
+    /// Records what the server reports about the resource in the status, for example usage
+    fn update_status_from_response(&self, _response: &Self::Response, _status: &mut Self::Status) {}
+
+    /// Called after an existing resource was updated on the server, with the new status
+    async fn after_update(
+        &self,
+        _context: &ContextData,
+        _namespace: &str,
+        _status: &Self::Status,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// When the resource has to be reconciled again, if that's sooner than the resync interval,
+    /// for example because it expires
+    fn get_requeue_after(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Whether the kubernetes resource itself should be deleted, for example because it expired
+    fn should_delete(&self) -> bool {
+        false
+    }
 }
 
 pub async fn sftpgo_api_resource_reconciler<TCrd>(
@@ -152,9 +476,9 @@ where
         + Sync
         + 'static,
     TCrd::DynamicType: Debug + Unpin + Eq + Hash + Clone + Default,
-    <TCrd as HasStatus>::Status: SftpgoStatus + Default,
-    AuthorizedSftpgoClient<RefreshableAdminAuthContext<SftpgoClient>>:
-        EasyRestSftpgoClient<TCrd::Request, TCrd::Response>,
+    <TCrd as HasStatus>::Status:
+        SftpgoStatus<Id = i32> + RequestStatus + Default + Clone + PartialEq,
+    OperatorSftpgoClient: EasyRestSftpgoClient<TCrd::Request, TCrd::Response>,
 {
     let name = resource.name_any();
 
@@ -173,7 +497,7 @@ where
     let sftpgo_name = resource.get_name().to_string();
     if resource.meta().deletion_timestamp.is_some() {
         info!("Resource {} is being deleted, cleaning up", sftpgo_name);
-        if let Some(status) = &resource.status() {
+        if let Some(status) = resource.status().filter(|s| !s.get_last_name().is_empty()) {
             api_client.delete(status.get_last_name()).await?;
             info!("Deleted old name {} from SFTPGo", status.get_last_name());
         }
@@ -182,16 +506,53 @@ where
 
         info!("Deleted {} from SFTPGo", sftpgo_name);
 
+        publish_event(
+            &context,
+            &resource,
+            EventType::Normal,
+            "Deleted",
+            "Delete",
+            format!("Deleted {} from SFTPGo", sftpgo_name),
+        )
+        .await;
+
         remove_finalizer::<TCrd>(context.kubernetes_client.clone(), &name, &namespace).await?;
 
         info!("Removed finalizer");
 
+        publish_event(
+            &context,
+            &resource,
+            EventType::Normal,
+            "FinalizerRemoved",
+            "Delete",
+            "Removed the finalizer, the resource can be deleted".to_string(),
+        )
+        .await;
+
+        return Ok(Action::await_change());
+    }
+
+    if resource.should_delete() {
+        info!(
+            "Resource {} should no longer exist, deleting it",
+            sftpgo_name
+        );
+
+        resource_api.delete(&name, &default()).await?;
+
+        // The finalizer removes the resource from SFTPGo once the deletion is observed
         return Ok(Action::await_change());
     }
 
     resource = ensure_finalizer(resource, context.kubernetes_client.clone()).await?;
 
-    if let Some(ref mut status) = &mut resource.status_mut() {
+    // A status can exist without a name, when the resource failed before it was ever created
+    let has_last_name = resource
+        .status()
+        .is_some_and(|s| !s.get_last_name().is_empty());
+
+    if let Some(ref mut status) = resource.status_mut().as_mut().filter(|_| has_last_name) {
         if status.get_last_name() != sftpgo_name {
             info!(
                 "Name changed from {} to {}, deleting old resource",
@@ -201,20 +562,36 @@ where
 
             api_client.delete(status.get_last_name()).await?;
 
+            let note = format!(
+                "Renamed from {} to {}, deleted the old name from SFTPGo",
+                status.get_last_name(),
+                sftpgo_name
+            );
+
             status.set_last_name(&sftpgo_name);
 
             resource = resource_api
                 .patch_status(&name, &default(), &Patch::Merge(resource))
                 .await?;
+
+            publish_event(
+                &context,
+                &resource,
+                EventType::Normal,
+                "Renamed",
+                "Update",
+                note,
+            )
+            .await;
         } else {
             info!("Name did not change, no need to delete old resource");
         }
     } else {
-        info!("No status set");
+        info!("No name recorded in the status");
 
         let status = resource.status_mut();
 
-        let mut s = TCrd::Status::default();
+        let mut s = status.clone().unwrap_or_default();
         s.set_last_name(&sftpgo_name);
 
         *status = Some(s);
@@ -224,13 +601,116 @@ where
             .await?;
     }
 
-    let request = resource.get_request(&context, &namespace).await?;
+    let mut request = resource.get_request(&context, &namespace).await?;
+
+    let mut status = resource.status().cloned().unwrap_or_default();
+    resource.update_status(&request, &mut status);
+
+    let uid = resource.uid().unwrap_or_default();
+    let request_hash = hash_request(&uid, &request);
+    let request_changed = status.get_request_hash() != Some(request_hash.as_str());
+    let field_hashes = hash_fields(&uid, &serde_json::to_value(&request)?);
+    let changed_fields =
+        find_changed_fields(status.get_field_hashes(), &field_hashes, request_changed);
+    status.set_request_hash(Some(request_hash));
+    status.set_field_hashes(Some(field_hashes));
+
+    if let Some(raw_existing) = api_client.get_raw(&sftpgo_name).await? {
+        let existing: TCrd::Response = serde_json::from_value(raw_existing.clone())?;
+
+        resource.validate_update(&existing, &request)?;
+        resource.prepare_update(&mut request);
+        resource.update_status_from_response(&existing, &mut status);
+
+        // Merged with the raw JSON, as the response type doesn't know every field SFTPGo has
+        let desired = match resource.get_field_management() {
+            FieldManagement::Full => serde_json::to_value(&request)?,
+            FieldManagement::Partial => {
+                merge_json(raw_existing.clone(), serde_json::to_value(&request)?)
+            }
+        };
+
+        let compare_unset = resource.get_field_management() == FieldManagement::Full;
+        // Differences in fields changed in the specification are expected, so they aren't drift
+        let drifted_fields: Vec<String> = find_drift(&desired, &raw_existing, compare_unset)
+            .into_iter()
+            .filter(|path| !changed_fields.is_changed(top_level_field(path)))
+            .collect();
+        let drifted = !drifted_fields.is_empty();
 
-    if api_client.get(&sftpgo_name).await?.is_some() {
-        info!("Updating resource {}", sftpgo_name);
+        if request_changed || drifted {
+            info!("Updating resource {}", sftpgo_name);
 
-        api_client.update(&request).await?;
-        info!("Updated resource {}", sftpgo_name);
+            api_client.update_raw(&sftpgo_name, &desired).await?;
+            info!("Updated resource {}", sftpgo_name);
+
+            resource.after_update(&context, &namespace, &status).await?;
+        } else {
+            debug!("Resource {} is in sync with SFTPGo", sftpgo_name);
+        }
+
+        if request_changed {
+            publish_event(
+                &context,
+                &resource,
+                EventType::Normal,
+                "Updated",
+                "Update",
+                format!("Updated {} in SFTPGo", sftpgo_name),
+            )
+            .await;
+        }
+
+        if drifted {
+            let message = format!(
+                "Reverted changes made in SFTPGo to {}",
+                drifted_fields.join(", ")
+            );
+            warn!("{} {}", sftpgo_name, message);
+
+            let kind = TCrd::kind(&default()).to_string();
+            for field in &drifted_fields {
+                drift_corrections().with_label_values(&[&kind, field]).inc();
+            }
+
+            // Something that keeps changing the resource in SFTPGo would otherwise publish an
+            // event on every resync
+            let already_reported = status.get_conditions().is_some_and(|conditions| {
+                conditions.iter().any(|c| {
+                    c.type_ == CONDITION_DRIFTED && c.status == "True" && c.message == message
+                })
+            });
+
+            if !already_reported {
+                publish_event(
+                    &context,
+                    &resource,
+                    EventType::Warning,
+                    "DriftCorrected",
+                    "Update",
+                    message.clone(),
+                )
+                .await;
+            }
+
+            set_condition(
+                status.conditions_mut(),
+                CONDITION_DRIFTED,
+                true,
+                "DriftCorrected",
+                message,
+                resource.meta().generation,
+            );
+        } else {
+            set_condition(
+                status.conditions_mut(),
+                CONDITION_DRIFTED,
+                false,
+                "InSync",
+                "The resource in SFTPGo matches the specification".to_string(),
+                resource.meta().generation,
+            );
+        }
     } else {
         info!("Creating resource {}", sftpgo_name);
 
@@ -238,13 +718,29 @@ where
 
         info!("Created resource {}", sftpgo_name);
 
-        let status = resource.status_mut();
+        publish_event(
+            &context,
+            &resource,
+            EventType::Normal,
+            "Created",
+            "Create",
+            format!("Created {} in SFTPGo", sftpgo_name),
+        )
+        .await;
+
+        status.set_last_name(&sftpgo_name);
+        status.set_id(Some(created_resource.id()));
+        resource.update_status_from_response(&created_resource, &mut status);
+    }
 
-        let mut s = TCrd::Status::default();
-        s.set_last_name(&sftpgo_name);
-        s.set_id(Some(created_resource.id()));
+    set_reconcile_succeeded(&mut status, resource.meta().generation);
 
-        *status = Some(s);
+    let requeue_after = resource
+        .get_requeue_after()
+        .map_or(context.resync_interval, |d| d.min(context.resync_interval));
+
+    if resource.status() != Some(&status) {
+        *resource.status_mut() = Some(status);
 
         resource_api
             .patch_status(&name, &default(), &Patch::Merge(resource))
@@ -253,5 +749,75 @@ where
         info!("Updated status for resource {}", sftpgo_name);
     }
 
-    Ok(Action::await_change())
+    Ok(Action::requeue(requeue_after))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crds::{
+        SftpgoAdmin, SftpgoApiKey, SftpgoEventAction, SftpgoEventRule, SftpgoFolder, SftpgoGroup,
+        SftpgoIpListEntry, SftpgoRole, SftpgoShare, SftpgoUser,
+    };
+
+    fn assert_failure_patch_is_valid_status<TResource>()
+    where
+        TResource: CustomResourceExt + HasStatus,
+        TResource::Status: ReconcileStatus + Default + DeserializeOwned,
+    {
+        let failure = ReconcileFailure::from(&Error::NotReady("dependency".to_string()));
+
+        let mut status = TResource::Status::default();
+        set_reconcile_failed(&mut status, &failure, Some(1));
+        let patch = failure_patch(&status);
+
+        let crd = TResource::crd();
+        let status_schema = crd.spec.versions[0]
+            .schema
+            .as_ref()
+            .and_then(|s| s.open_api_v3_schema.as_ref())
+            .and_then(|s| s.properties.as_ref())
+            .and_then(|p| p.get("status"))
+            .expect("The status should be part of the schema");
+
+        for field in status_schema.required.iter().flatten() {
+            assert!(
+                patch["status"].get(field).is_some(),
+                "The status of {} requires {}, which the patch doesn't set",
+                TResource::crd_name(),
+                field
+            );
+        }
+
+        let patched: TResource::Status = serde_json::from_value(patch["status"].clone())
+            .expect("The patch should deserialize as a status");
+        assert_eq!(patched.get_last_error(), Some(failure.message.as_str()));
+    }
+
+    #[test]
+    fn first_failure_patch_is_a_valid_status() {
+        assert_failure_patch_is_valid_status::<SftpgoAdmin>();
+        assert_failure_patch_is_valid_status::<SftpgoApiKey>();
+        assert_failure_patch_is_valid_status::<SftpgoEventAction>();
+        assert_failure_patch_is_valid_status::<SftpgoEventRule>();
+        assert_failure_patch_is_valid_status::<SftpgoFolder>();
+        assert_failure_patch_is_valid_status::<SftpgoGroup>();
+        assert_failure_patch_is_valid_status::<SftpgoIpListEntry>();
+        assert_failure_patch_is_valid_status::<SftpgoRole>();
+        assert_failure_patch_is_valid_status::<SftpgoShare>();
+        assert_failure_patch_is_valid_status::<SftpgoUser>();
+    }
+
+    #[test]
+    fn only_fields_with_a_new_hash_are_changed() {
+        let previous = hash_fields("uid", &json!({"quota_size": 100, "email": "a@example.com"}));
+        let current = hash_fields("uid", &json!({"quota_size": 200, "email": "a@example.com"}));
+
+        let changed = find_changed_fields(Some(&previous), &current, true);
+
+        assert!(changed.is_changed("quota_size"));
+        assert!(!changed.is_changed("email"));
+        assert!(find_changed_fields(None, &current, true).is_changed("email"));
+        assert!(!find_changed_fields(None, &current, false).is_changed("email"));
+    }
 }
@@ -0,0 +1,66 @@
+use crate::reconciler::{ContextData, SftpgoResource};
+use crate::Error;
+use async_trait::async_trait;
+use crds::{FieldManagement, RoleReference, ServerReference, SftpgoRole, SftpgoStatus};
+use kube::Api;
+use sftpgo_client::roles::{RoleRequest, RoleResponse};
+
+pub async fn get_role_name(
+    namespace: &str,
+    crd_ref: &RoleReference,
+    context: &ContextData,
+) -> Result<String, Error> {
+    let ns = crd_ref.namespace.as_deref().unwrap_or(namespace);
+
+    let role_client: Api<SftpgoRole> = Api::namespaced(context.kubernetes_client.clone(), ns);
+
+    if let Some(role) = role_client.get_opt(&crd_ref.name).await? {
+        if let Some(status) = role.status {
+            if status.get_id().is_none() {
+                return Err(Error::NotReady(crd_ref.name.to_string()));
+            }
+
+            Ok(status.get_last_name().to_string())
+        } else {
+            Err(Error::NotReady(crd_ref.name.to_string()))
+        }
+    } else {
+        Err(Error::UserInput(format!(
+            "Role {} not found in namespace {}",
+            crd_ref.name, ns
+        )))
+    }
+}
+
+#[async_trait]
+impl SftpgoResource for SftpgoRole {
+    type Request = RoleRequest;
+    type Response = RoleResponse;
+
+    fn get_name(&self) -> &str {
+        &self.spec.configuration.name
+    }
+
+    async fn get_request(
+        &self,
+        _context: &ContextData,
+        _namespace: &str,
+    ) -> Result<Self::Request, Error> {
+        let role_configuration = &self.spec.configuration;
+
+        let request = RoleRequest {
+            name: role_configuration.name.clone(),
+            description: role_configuration.description.clone(),
+        };
+
+        Ok(request)
+    }
+
+    fn get_server_reference(&self) -> &ServerReference {
+        &self.spec.server_reference
+    }
+
+    fn get_field_management(&self) -> FieldManagement {
+        self.spec.field_management.unwrap_or_default()
+    }
+}
@@ -0,0 +1,67 @@
+use ring::digest::{digest, SHA256};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Hashes a secret value, so changes to it can be detected without storing the value. The salt
+/// should be unique per resource, for example its uid, so equal values don't give equal hashes.
+pub fn hash_secret(salt: &str, value: &str) -> String {
+    let hash = digest(&SHA256, format!("{}:{}", salt, value).as_bytes());
+
+    hash.as_ref().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Hashes a request, so changes to it can be detected. Object keys are sorted first, as maps
+/// don't serialize in a stable order.
+pub fn hash_request<T: Serialize>(salt: &str, request: &T) -> String {
+    let value = serde_json::to_value(request)
+        .map(sort_keys)
+        .unwrap_or_default();
+
+    hash_secret(salt, &value.to_string())
+}
+
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k, sort_keys(v)))
+                    .collect(),
+            )
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(sort_keys).collect()),
+        value => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn hash_depends_on_salt_and_value() {
+        let hash = hash_secret("salt", "value");
+
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, hash_secret("salt", "value"));
+        assert_ne!(hash, hash_secret("other", "value"));
+        assert_ne!(hash, hash_secret("salt", "other"));
+    }
+
+    #[test]
+    fn request_hash_ignores_key_order() {
+        let first = json!({"a": 1, "b": {"c": 2, "d": 3}});
+        let second = json!({"b": {"d": 3, "c": 2}, "a": 1});
+
+        assert_eq!(hash_request("salt", &first), hash_request("salt", &second));
+        assert_ne!(
+            hash_request("salt", &first),
+            hash_request("salt", &json!({"a": 2}))
+        );
+    }
+}
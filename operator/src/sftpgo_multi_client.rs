@@ -1,5 +1,6 @@
-use crate::consts::{SECRET_KEY_PASSWORD, SECRET_KEY_URL, SECRET_KEY_USERNAME};
+use crate::consts::{SECRET_KEY_API_KEY, SECRET_KEY_PASSWORD, SECRET_KEY_URL, SECRET_KEY_USERNAME};
 use crate::reconciler::{ContextData, Error};
+use crate::secret_hash::hash_secret;
 use crds::{ConnectionOverride, ServerReference};
 use k8s_openapi::api::core::v1::Secret;
 use k8s_openapi::ByteString;
@@ -7,10 +8,13 @@ use kube::Api;
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
 use sftpgo_client::{
-    AuthorizedSftpgoClient, CreatedFrom, Creates, EasyRestSftpgoClient, Named,
-    RefreshableAdminAuthContext, SftpgoClient, SftpgoRestClient,
+    ApiKeyAuthContext, AuthContext, AuthorizedSftpgoClient, CreatedFrom, Creates,
+    EasyRestSftpgoClient, Named, RefreshableAdminAuthContext, RefreshableUserAuthContext,
+    SftpgoClient, SftpgoRestClient,
 };
 use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::future::Future;
 use std::sync::Arc;
 use tokio::sync::{Mutex, RwLock};
 
@@ -38,9 +42,11 @@ impl SftpgoMultiClient {
     }
 }
 
-type AuthorizedClients = Arc<
-    RwLock<HashMap<String, Arc<AuthorizedSftpgoClient<RefreshableAdminAuthContext<SftpgoClient>>>>>,
->;
+pub type OperatorSftpgoClient = AuthorizedSftpgoClient<Arc<dyn AuthContext>>;
+
+/// The clients by credential kind and username, together with a hash of the credentials they were
+/// created with, so a client is replaced rather than kept next to the new one when they change
+type AuthorizedClients = Arc<RwLock<HashMap<String, (String, Arc<OperatorSftpgoClient>)>>>;
 
 #[derive(Clone)]
 pub struct KnownSftpgoClient {
@@ -56,40 +62,87 @@ impl KnownSftpgoClient {
         }
     }
 
-    pub async fn get_authorized_client<TRequest, TResponse>(
+    pub async fn get_authorized_client(
+        &self,
+        credentials: &ConnectionCredentials,
+    ) -> Result<Arc<OperatorSftpgoClient>, Error> {
+        match credentials {
+            ConnectionCredentials::Admin { username, password } => {
+                self.get_cached_client(format!("admin:{}", username), password, || async {
+                    let ctx: Arc<dyn AuthContext> = Arc::new(
+                        RefreshableAdminAuthContext::new(
+                            username.to_string(),
+                            password.to_string(),
+                            self.client.clone(),
+                        )
+                        .await?,
+                    );
+                    Ok(ctx)
+                })
+                .await
+            }
+            ConnectionCredentials::ApiKey(api_key) => {
+                self.get_cached_client("api-key".to_string(), api_key, || async {
+                    let ctx: Arc<dyn AuthContext> =
+                        Arc::new(ApiKeyAuthContext::new(api_key.to_string()));
+                    Ok(ctx)
+                })
+                .await
+            }
+        }
+    }
+
+    /// Gets a client authorized as a SFTPGo user, for the user REST API
+    pub async fn get_user_client(
         &self,
         username: &str,
         password: &str,
-    ) -> Result<Box<Arc<dyn SftpgoRestClient<TRequest, TResponse>>>, Error>
+    ) -> Result<Arc<OperatorSftpgoClient>, Error> {
+        self.get_cached_client(format!("user:{}", username), password, || async {
+            let ctx: Arc<dyn AuthContext> = Arc::new(
+                RefreshableUserAuthContext::new(
+                    username.to_string(),
+                    password.to_string(),
+                    self.client.clone(),
+                )
+                .await?,
+            );
+            Ok(ctx)
+        })
+        .await
+    }
+
+    async fn get_cached_client<F, Fut>(
+        &self,
+        cache_key: String,
+        secret: &str,
+        create_auth_context: F,
+    ) -> Result<Arc<OperatorSftpgoClient>, Error>
     where
-        TRequest: Serialize + Sync + Named + Creates<TResponse>,
-        TResponse: for<'de> Deserialize<'de> + CreatedFrom<TRequest>,
-        AuthorizedSftpgoClient<RefreshableAdminAuthContext<SftpgoClient>>:
-            EasyRestSftpgoClient<TRequest, TResponse>,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Arc<dyn AuthContext>, Error>>,
     {
+        let credentials_hash = hash_secret(&cache_key, secret);
+
         {
             let all = self.authorized_clients.read().await;
 
-            if let Some(client) = all.get(username) {
-                let autho = client.clone();
-                return Ok(Box::new(autho));
+            if let Some((hash, client)) = all.get(&cache_key) {
+                if *hash == credentials_hash {
+                    return Ok(client.clone());
+                }
             }
         }
 
         {
             let mut all = self.authorized_clients.write().await;
 
-            let ctx = RefreshableAdminAuthContext::new(
-                username.to_string(),
-                password.to_string(),
-                self.client.clone(),
-            )
-            .await?;
+            let ctx = create_auth_context().await?;
 
             let new_client = self.client.with_auth_context(ctx);
             let a = Arc::new(new_client);
-            all.insert(username.to_string(), a.clone());
-            Ok(Box::new(a))
+            all.insert(cache_key, (credentials_hash, a.clone()));
+            Ok(a)
         }
     }
 }
@@ -97,14 +150,64 @@ impl KnownSftpgoClient {
 pub async fn get_api_client<TRequest, TResponse>(
     server_ref: &ServerReference,
     context: &ContextData,
-    namespace: &String,
+    namespace: &str,
 ) -> Result<Box<Arc<dyn SftpgoRestClient<TRequest, TResponse>>>, Error>
 where
     TRequest: Serialize + Sync + Named + Creates<TResponse>,
     TResponse: for<'de> Deserialize<'de> + CreatedFrom<TRequest>,
-    AuthorizedSftpgoClient<RefreshableAdminAuthContext<SftpgoClient>>:
-        EasyRestSftpgoClient<TRequest, TResponse>,
+    OperatorSftpgoClient: EasyRestSftpgoClient<TRequest, TResponse>,
 {
+    let authorized_client = get_authorized_client(server_ref, context, namespace).await?;
+
+    Ok(Box::new(authorized_client))
+}
+
+/// Gets a client authorized against the referenced server, for requests that don't fit the
+/// generic rest client.
+pub async fn get_authorized_client(
+    server_ref: &ServerReference,
+    context: &ContextData,
+    namespace: &str,
+) -> Result<Arc<OperatorSftpgoClient>, Error> {
+    let connection_info = get_connection_info(server_ref, context, namespace).await?;
+
+    let c = context
+        .sftpgo_client
+        .get_client(&connection_info.uid, connection_info.url.clone())
+        .await;
+
+    let authorized_client = c
+        .get_authorized_client(&connection_info.credentials)
+        .await?;
+
+    Ok(authorized_client)
+}
+
+/// Gets a client authorized as a SFTPGo user against the referenced server, for the user
+/// REST API. The client is cached like the admin clients, so its token is reused between
+/// reconciles rather than logging in as the user every time.
+pub async fn get_user_client(
+    server_ref: &ServerReference,
+    context: &ContextData,
+    namespace: &str,
+    username: &str,
+    password: &str,
+) -> Result<Arc<OperatorSftpgoClient>, Error> {
+    let connection_info = get_connection_info(server_ref, context, namespace).await?;
+
+    let c = context
+        .sftpgo_client
+        .get_client(&connection_info.uid, connection_info.url.clone())
+        .await;
+
+    c.get_user_client(username, password).await
+}
+
+async fn get_connection_info(
+    server_ref: &ServerReference,
+    context: &ContextData,
+    namespace: &str,
+) -> Result<ConnectionInfo, Error> {
     let connection_info = if let Some(connection_secret) = &server_ref.connection_secret {
         if server_ref.name.is_some() || server_ref.namespace.is_some() {
             return Err(Error::UserInput(
@@ -113,10 +216,10 @@ where
             ));
         }
 
-        let secret_namespace = connection_secret.namespace.as_ref().unwrap_or(namespace);
+        let secret_namespace = connection_secret.namespace.as_deref().unwrap_or(namespace);
         let secret_name = &connection_secret.name;
 
-        get_admin_secret_values(
+        get_connection_secret_values(
             context,
             secret_namespace,
             secret_name,
@@ -124,11 +227,11 @@ where
         )
         .await?
     } else if let Some(name) = &server_ref.name {
-        let target_namespace = server_ref.namespace.as_ref().unwrap_or(namespace);
+        let target_namespace = server_ref.namespace.as_deref().unwrap_or(namespace);
 
         let admin_user_secret_name = format!("{}-admin-user", name);
 
-        get_admin_secret_values(
+        get_connection_secret_values(
             context,
             target_namespace,
             &admin_user_secret_name,
@@ -143,27 +246,35 @@ where
 
     trace!("Using connection info: {:?}", connection_info);
 
-    let c = context
-        .sftpgo_client
-        .get_client(&connection_info.uid, connection_info.url.clone())
-        .await;
+    Ok(connection_info)
+}
 
-    let authorized_client = c
-        .get_authorized_client(&connection_info.username, &connection_info.password)
-        .await?;
+pub enum ConnectionCredentials {
+    Admin { username: String, password: String },
+    ApiKey(String),
+}
 
-    Ok(authorized_client)
+/// Leaves out the password and api key, so connection details can be logged
+impl Debug for ConnectionCredentials {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConnectionCredentials::Admin { username, .. } => f
+                .debug_struct("Admin")
+                .field("username", username)
+                .finish_non_exhaustive(),
+            ConnectionCredentials::ApiKey(_) => f.write_str("ApiKey"),
+        }
+    }
 }
 
 #[derive(Debug)]
 struct ConnectionInfo {
     url: Url,
-    username: String,
-    password: String,
+    credentials: ConnectionCredentials,
     uid: String,
 }
 
-async fn get_admin_secret_values(
+async fn get_connection_secret_values(
     context: &ContextData,
     secret_namespace: &str,
     secret_name: &str,
@@ -176,18 +287,29 @@ async fn get_admin_secret_values(
         debug!("Secret {} found", secret_name);
 
         if let Some(sd) = &secret.data {
-            let ((url, username), password) = sd
-                .get(SECRET_KEY_URL)
-                .zip(sd.get(SECRET_KEY_USERNAME))
-                .zip(sd.get(SECRET_KEY_PASSWORD))
-                .ok_or(Error::UserInput(format!(
-                    "Secret {} does not contain all required keys. Expected '{}', '{}' and '{}'",
-                    secret_name, SECRET_KEY_USERNAME, SECRET_KEY_URL, SECRET_KEY_PASSWORD
-                )))?;
+            let url = sd.get(SECRET_KEY_URL).ok_or(Error::UserInput(format!(
+                "Secret {} does not contain the required key '{}'",
+                secret_name, SECRET_KEY_URL
+            )))?;
+
+            let credentials = if let Some(api_key) = sd.get(SECRET_KEY_API_KEY) {
+                ConnectionCredentials::ApiKey(parse_secret_value(api_key)?.to_string())
+            } else {
+                let (username, password) = sd
+                    .get(SECRET_KEY_USERNAME)
+                    .zip(sd.get(SECRET_KEY_PASSWORD))
+                    .ok_or(Error::UserInput(format!(
+                        "Secret {} does not contain credentials. Expected either '{}' or '{}' and '{}'",
+                        secret_name, SECRET_KEY_API_KEY, SECRET_KEY_USERNAME, SECRET_KEY_PASSWORD
+                    )))?;
+
+                ConnectionCredentials::Admin {
+                    username: parse_secret_value(username)?.to_string(),
+                    password: parse_secret_value(password)?.to_string(),
+                }
+            };
 
             let url = parse_secret_value(url)?;
-            let username = parse_secret_value(username)?;
-            let password = parse_secret_value(password)?;
 
             let u = Url::parse(url).map_err(|e| {
                 Error::UserInput(format!(
@@ -198,8 +320,7 @@ async fn get_admin_secret_values(
 
             let mut info = ConnectionInfo {
                 url: u,
-                username: username.to_string(),
-                password: password.to_string(),
+                credentials,
                 uid: secret.metadata.uid.unwrap(),
             };
             if let Some(o) = connection_override {
@@ -210,11 +331,18 @@ async fn get_admin_secret_values(
 
                     info.url = u;
                 }
-                if let Some(username) = &o.username {
-                    info.username = username.clone();
-                }
-                if let Some(password) = &o.password {
-                    info.password = password.clone();
+                if o.username.is_some() || o.password.is_some() {
+                    let (username, password) = match &info.credentials {
+                        ConnectionCredentials::Admin { username, password } => {
+                            (username.clone(), password.clone())
+                        }
+                        ConnectionCredentials::ApiKey(_) => Default::default(),
+                    };
+
+                    info.credentials = ConnectionCredentials::Admin {
+                        username: o.username.clone().unwrap_or(username),
+                        password: o.password.clone().unwrap_or(password),
+                    };
                 }
             }
             Ok(info)
@@ -236,3 +364,20 @@ fn parse_secret_value(value: &ByteString) -> Result<&str, Error> {
     std::str::from_utf8(&value.0)
         .map_err(|e| Error::UserInput(format!("Secret contains invalid UTF-8: {}", e)))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn debug_leaves_out_secrets() {
+        let admin = ConnectionCredentials::Admin {
+            username: "admin".to_string(),
+            password: "admin-password".to_string(),
+        };
+        let api_key = ConnectionCredentials::ApiKey("key-id.key-secret".to_string());
+
+        assert_eq!(format!("{:?}", admin), "Admin { username: \"admin\", .. }");
+        assert_eq!(format!("{:?}", api_key), "ApiKey");
+    }
+}
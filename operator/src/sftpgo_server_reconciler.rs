@@ -1,4 +1,5 @@
 use crate::consts::{SECRET_KEY_PASSWORD, SECRET_KEY_URL, SECRET_KEY_USERNAME};
+use crate::events::publish_event;
 use crate::reconciler::Error;
 use crate::viper_environment_serializer::ViperEnvironmentSerializer;
 use crate::{default, ContextData};
@@ -9,6 +10,7 @@ use k8s_openapi::apimachinery::pkg::apis::meta::v1::{LabelSelector, ObjectMeta,
 use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
 use kube::api::{Patch, PatchParams};
 use kube::runtime::controller::Action;
+use kube::runtime::events::EventType;
 use kube::{Api, Client, Resource, ResourceExt};
 use rand::distributions::{Alphanumeric, DistString};
 use serde::Serialize;
@@ -38,7 +40,21 @@ pub async fn reconcile_sftpgo_server(
         return Ok(Action::await_change());
     }
 
-    controller.ensure_secret().await?;
+    if controller.ensure_secret().await? {
+        publish_event(
+            &context,
+            resource.as_ref(),
+            EventType::Normal,
+            "Created",
+            "Create",
+            format!(
+                "Created the admin user secret {}",
+                controller.get_admin_user_secret_name()
+            ),
+        )
+        .await;
+    }
+
     controller.ensure_service().await?;
     controller.ensure_deployment().await?;
 
@@ -326,7 +342,8 @@ impl DeploymentController {
         Ok(())
     }
 
-    async fn ensure_secret(&self) -> Result<String, Error> {
+    /// Returns whether the secret was created
+    async fn ensure_secret(&self) -> Result<bool, Error> {
         let name = &self.name;
         let namespace = &self.namespace;
 
@@ -352,8 +369,9 @@ impl DeploymentController {
 
         // Intentionally not using the patch api as we cannot ensure we generate the same password
         // every time, and that would cause issues with actually accessing sftpgo.
-        let admin_user_secret_name = format!("{}-admin-user", name);
-        if let Some(ref mut existing) = secret_api.get_opt(&admin_user_secret_name).await? {
+        let admin_user_secret_name = self.get_admin_user_secret_name();
+        let mut existing = secret_api.get_opt(&admin_user_secret_name).await?;
+        let created = if let Some(ref mut existing) = existing {
             debug!("Secret {} already exists", admin_user_secret_name);
 
             let mut changed = false;
@@ -376,6 +394,8 @@ impl DeploymentController {
                     .replace(&admin_user_secret_name, &default(), existing)
                     .await?;
             }
+
+            false
         } else {
             debug!("Creating secret {}", admin_user_secret_name);
             let mut secret_data: BTreeMap<String, String> = BTreeMap::new();
@@ -408,7 +428,9 @@ impl DeploymentController {
 
             secret_api.create(&default(), &admin_user_secret).await?;
             debug!("Secret {} created", admin_user_secret_name);
-        }
-        Ok(admin_user_secret_name)
+
+            true
+        };
+        Ok(created)
     }
 }
@@ -0,0 +1,314 @@
+use crate::conditions::set_reconcile_succeeded;
+use crate::default;
+use crate::finalizers::{ensure_finalizer, remove_finalizer};
+use crate::reconciler::{ContextData, Error};
+use crate::secret_hash::hash_secret;
+use crate::sftpgo_multi_client::{get_user_client, OperatorSftpgoClient};
+use crate::user_reconciler::get_user_password;
+use crate::value_from::get_value_from;
+use crds::{LoginMethod, SftpgoProtocol, SftpgoShare, SftpgoShareScope, SftpgoStatus, SftpgoUser};
+use kube::api::Patch;
+use kube::runtime::controller::Action;
+use kube::{Api, Resource, ResourceExt};
+use reqwest::Url;
+use sftpgo_client::shares::{ShareClient, ShareRequest, ShareResponse, ShareScope};
+use sftpgo_client::SftpgoClientBase;
+use std::sync::Arc;
+
+pub async fn reconcile_sftpgo_share(
+    resource: Arc<SftpgoShare>,
+    context: Arc<ContextData>,
+) -> Result<Action, Error> {
+    let name = resource.name_any();
+
+    let namespace = resource.namespace().ok_or(Error::UserInput(
+        "Expected SftpgoShare resource to be namespaced. Can't deploy to unknown namespace."
+            .to_string(),
+    ))?;
+
+    let resource_api: Api<SftpgoShare> =
+        Api::namespaced(context.kubernetes_client.clone(), &namespace);
+
+    let mut resource = resource_api.get(&name).await?;
+
+    let share_id = resource.status.as_ref().and_then(|s| s.share_id.clone());
+
+    let user_reference = &resource.spec.user_reference;
+    let user_namespace = user_reference.namespace.as_deref().unwrap_or(&namespace);
+    let user_api: Api<SftpgoUser> =
+        Api::namespaced(context.kubernetes_client.clone(), user_namespace);
+    let user = user_api.get_opt(&user_reference.name).await?;
+
+    if resource.meta().deletion_timestamp.is_some() {
+        info!("Share {namespace}/{name} is being deleted, cleaning up");
+
+        // SFTPGo deletes the shares of a user together with the user, so there is only
+        // something to clean up while the user exists
+        match (&share_id, &user) {
+            (Some(share_id), Some(user)) if is_ready(user) => {
+                let api_client = get_client_for_user(user, user_namespace, &context).await?;
+                api_client.delete_share(share_id).await?;
+                info!("Deleted share {} from SFTPGo", share_id);
+            }
+            _ => {
+                debug!("Share {namespace}/{name} has no share in SFTPGo to delete");
+            }
+        }
+
+        remove_finalizer::<SftpgoShare>(context.kubernetes_client.clone(), &name, &namespace)
+            .await?;
+
+        info!("Removed finalizer");
+
+        return Ok(Action::await_change());
+    }
+
+    let user = user.ok_or_else(|| {
+        Error::UserInput(format!(
+            "SftpgoUser {} not found in namespace {}",
+            user_reference.name, user_namespace
+        ))
+    })?;
+
+    if !is_ready(&user) {
+        return Err(Error::NotReady(user_reference.name.to_string()));
+    }
+
+    let username = user
+        .status
+        .as_ref()
+        .map(|s| s.get_last_name().to_string())
+        .unwrap_or_default();
+
+    let api_client = get_client_for_user(&user, user_namespace, &context).await?;
+
+    resource = ensure_finalizer(resource, context.kubernetes_client.clone()).await?;
+
+    let request = get_request(&resource, &context, &namespace).await?;
+
+    let existing_share = if let Some(share_id) = &share_id {
+        api_client.get_share(share_id).await?
+    } else {
+        None
+    };
+
+    let uid = resource.uid().unwrap_or_default();
+    let password_hash = request
+        .password
+        .as_ref()
+        .map(|password| hash_secret(&uid, password));
+    let previous_password_hash = resource
+        .status
+        .as_ref()
+        .and_then(|s| s.password_hash.clone());
+
+    let share_id = if let Some(existing_share) = existing_share {
+        if share_matches_request(&existing_share, &request)
+            && password_hash == previous_password_hash
+        {
+            debug!("Share {} is up to date", existing_share.id);
+        } else {
+            info!("Updating share {}", existing_share.id);
+
+            api_client
+                .update_share(&existing_share.id, &request)
+                .await?;
+
+            info!("Updated share {}", existing_share.id);
+        }
+
+        existing_share.id
+    } else {
+        info!("Creating share for {namespace}/{name} as user {username}");
+
+        let id = api_client.create_share(&request).await?;
+
+        info!("Created share {}", id);
+
+        id
+    };
+
+    let url = get_share_url(&resource, &api_client, &share_id)?;
+
+    let mut s = resource.status.clone().unwrap_or_default();
+    s.share_id = Some(share_id);
+    s.username = Some(username);
+    s.url = Some(url);
+    s.password_hash = password_hash;
+    set_reconcile_succeeded(&mut s, resource.metadata.generation);
+
+    if resource.status.as_ref() != Some(&s) {
+        resource.status = Some(s);
+
+        resource_api
+            .patch_status(&name, &default(), &Patch::Merge(resource))
+            .await?;
+
+        info!("Updated status for share {namespace}/{name}");
+    }
+
+    Ok(Action::await_change())
+}
+
+/// Compares the share in SFTPGo with the request. The password can't be read back, so changes
+/// to it are detected with the hash in the status instead.
+fn share_matches_request(existing: &ShareResponse, request: &ShareRequest) -> bool {
+    existing.name == request.name
+        && existing.description.as_deref().unwrap_or_default()
+            == request.description.as_deref().unwrap_or_default()
+        && existing.scope == request.scope
+        && existing.paths.as_deref().unwrap_or_default() == request.paths.as_slice()
+        && existing.expires_at.unwrap_or_default() == request.expires_at.unwrap_or_default()
+        && existing.max_tokens.unwrap_or_default() == request.max_tokens.unwrap_or_default()
+        && existing.allow_from.as_deref().unwrap_or_default()
+            == request.allow_from.as_deref().unwrap_or_default()
+}
+
+fn is_ready(user: &SftpgoUser) -> bool {
+    user.status.as_ref().and_then(|s| s.get_id()).is_some()
+}
+
+async fn get_client_for_user(
+    user: &SftpgoUser,
+    user_namespace: &str,
+    context: &ContextData,
+) -> Result<Arc<OperatorSftpgoClient>, Error> {
+    let username = user
+        .status
+        .as_ref()
+        .map(|s| s.get_last_name())
+        .unwrap_or_default();
+
+    // Shares are managed through the user REST API, which requires logging in as the user
+    // with its password
+    let filters = user.spec.configuration.filters.as_ref();
+    let password_login_denied = filters
+        .and_then(|f| f.denied_login_methods.as_ref())
+        .is_some_and(|methods| methods.contains(&LoginMethod::Password));
+    let http_denied = filters
+        .and_then(|f| f.denied_protocols.as_ref())
+        .is_some_and(|protocols| protocols.contains(&SftpgoProtocol::Http));
+
+    if password_login_denied || http_denied {
+        return Err(Error::UserInput(format!(
+            "Shares are managed by logging in to the user REST API with the password of the user, \
+            which the filters of {} deny",
+            username
+        )));
+    }
+
+    let password = get_user_password(user, context, user_namespace)
+        .await?
+        .ok_or_else(|| {
+            Error::UserInput(format!(
+                "Shares are managed by logging in to the user REST API with the password of the \
+                user, but {} has no password. Set password, passwordFrom or generatePassword on it",
+                username
+            ))
+        })?;
+
+    get_user_client(
+        &user.spec.server_reference,
+        context,
+        user_namespace,
+        username,
+        &password,
+    )
+    .await
+}
+
+fn get_share_url(
+    resource: &SftpgoShare,
+    api_client: &OperatorSftpgoClient,
+    share_id: &str,
+) -> Result<String, Error> {
+    let path = format!("/web/client/pubshares/{}", share_id);
+
+    let url = if let Some(public_url) = &resource.spec.public_url {
+        Url::parse(public_url)
+            .and_then(|u| u.join(&path))
+            .map_err(|e| Error::UserInput(format!("publicUrl is not a valid URL: {}", e)))?
+    } else {
+        api_client.url_for(&path)?
+    };
+
+    Ok(url.to_string())
+}
+
+async fn get_request(
+    resource: &SftpgoShare,
+    context: &ContextData,
+    namespace: &str,
+) -> Result<ShareRequest, Error> {
+    let configuration = &resource.spec.configuration;
+
+    let scope = match configuration.scope.unwrap_or_default() {
+        SftpgoShareScope::Read => ShareScope::Read,
+        SftpgoShareScope::Write => ShareScope::Write,
+        SftpgoShareScope::ReadWrite => ShareScope::ReadWrite,
+    };
+
+    if scope != ShareScope::Read && configuration.paths.len() != 1 {
+        return Err(Error::UserInput(
+            "Shares with the Write or ReadWrite scope must have exactly one path".to_string(),
+        ));
+    }
+
+    let password = if let Some(password_from) = &configuration.password_from {
+        Some(get_value_from(password_from, context, namespace).await?)
+    } else {
+        None
+    };
+
+    Ok(ShareRequest {
+        name: configuration.name.clone(),
+        description: configuration.description.clone(),
+        scope,
+        paths: configuration.paths.clone(),
+        expires_at: configuration
+            .expires_at
+            .as_ref()
+            .map(|t| t.0.timestamp_millis()),
+        password,
+        max_tokens: configuration.max_tokens,
+        allow_from: configuration.allow_from.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unset_values_match_empty_values_from_sftpgo() {
+        let request = ShareRequest {
+            name: "reports".to_string(),
+            scope: ShareScope::Read,
+            paths: vec!["/reports".to_string()],
+            password: Some("share-password".to_string()),
+            ..Default::default()
+        };
+        let existing = ShareResponse {
+            id: "share-id".to_string(),
+            name: "reports".to_string(),
+            description: Some(String::new()),
+            scope: ShareScope::Read,
+            paths: Some(vec!["/reports".to_string()]),
+            username: Some("test".to_string()),
+            expires_at: Some(0),
+            max_tokens: Some(0),
+            used_tokens: Some(3),
+            allow_from: Some(vec![]),
+            ..Default::default()
+        };
+
+        assert!(share_matches_request(&existing, &request));
+
+        let request = ShareRequest {
+            paths: vec!["/reports".to_string(), "/invoices".to_string()],
+            ..request
+        };
+
+        assert!(!share_matches_request(&existing, &request));
+    }
+}
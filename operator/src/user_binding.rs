@@ -0,0 +1,183 @@
+use crate::consts::{
+    SECRET_KEY_HOST, SECRET_KEY_PASSWORD, SECRET_KEY_PORT, SECRET_KEY_PROVIDER, SECRET_KEY_TYPE,
+    SECRET_KEY_USERNAME,
+};
+use crate::default;
+use crate::reconciler::{ContextData, Error};
+use crds::{SftpgoServer, SftpgoUser};
+use k8s_openapi::api::core::v1::Secret;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use k8s_openapi::ByteString;
+use kube::{Api, Resource, ResourceExt};
+use rand::distributions::{Alphanumeric, DistString};
+use std::collections::BTreeMap;
+
+// The ports used for bindings without a port, matching the ports exposed by the server service
+const DEFAULT_SFTP_PORT: i64 = 2022;
+const DEFAULT_FTP_PORT: i64 = 21;
+const DEFAULT_HTTP_PORT: i64 = 8080;
+const BINDING_TYPE: &str = "sftp";
+
+pub fn get_binding_secret_name(user: &SftpgoUser) -> String {
+    user.spec
+        .binding_secret_name
+        .clone()
+        .unwrap_or_else(|| format!("{}-binding", user.name_any()))
+}
+
+/// Creates or updates the servicebinding.io secret with the connection details and the generated
+/// password of the user
+pub async fn ensure_binding_secret(
+    user: &SftpgoUser,
+    context: &ContextData,
+    namespace: &str,
+) -> Result<(), Error> {
+    let secret_name = get_binding_secret_name(user);
+
+    let secret_api: Api<Secret> = Api::namespaced(context.kubernetes_client.clone(), namespace);
+    let existing = secret_api.get_opt(&secret_name).await?;
+
+    // Keep the password once generated, as workloads might already be using it
+    let password = existing
+        .as_ref()
+        .and_then(|s| s.data.as_ref())
+        .and_then(|d| d.get(SECRET_KEY_PASSWORD))
+        .and_then(|p| String::from_utf8(p.0.clone()).ok())
+        .unwrap_or_else(|| Alphanumeric.sample_string(&mut rand::thread_rng(), 50));
+
+    let mut secret_data: BTreeMap<String, String> = BTreeMap::new();
+    secret_data.insert(SECRET_KEY_TYPE.to_string(), BINDING_TYPE.to_string());
+    secret_data.insert(SECRET_KEY_PROVIDER.to_string(), "sftpgo".to_string());
+    secret_data.insert(
+        SECRET_KEY_USERNAME.to_string(),
+        user.spec.configuration.username.clone(),
+    );
+    secret_data.insert(SECRET_KEY_PASSWORD.to_string(), password);
+    add_server_endpoint(user, context, namespace, &mut secret_data).await?;
+
+    let secret_data: BTreeMap<String, ByteString> = secret_data
+        .into_iter()
+        .map(|(key, value)| (key, ByteString(value.into_bytes())))
+        .collect();
+
+    let secret = Secret {
+        metadata: ObjectMeta {
+            name: Some(secret_name.clone()),
+            // Always comes from the api, so according to the docs it's safe to unwrap
+            owner_references: Some(vec![user.controller_owner_ref(&()).unwrap()]),
+            ..default()
+        },
+        type_: Some(format!("servicebinding.io/{}", BINDING_TYPE)),
+        data: Some(secret_data),
+        ..default()
+    };
+
+    if let Some(existing) = existing {
+        if existing.data != secret.data {
+            debug!("Replacing binding secret {}", secret_name);
+            secret_api
+                .replace(&secret_name, &default(), &secret)
+                .await?;
+        }
+    } else {
+        debug!("Creating binding secret {}", secret_name);
+        secret_api.create(&default(), &secret).await?;
+    }
+
+    Ok(())
+}
+
+/// Adds the host and ports of the referenced server. The ports are read from the server
+/// configuration, so the server has to be managed by the operator. Every binding the server
+/// service exposes gets a key named after its protocol, like `sftp-port`, and further bindings of
+/// the same protocol are numbered, like `sftp-port-1`. WebDAV isn't exposed by the service, so it
+/// isn't published. `port` is the first SFTP port, if SFTP is enabled.
+async fn add_server_endpoint(
+    user: &SftpgoUser,
+    context: &ContextData,
+    namespace: &str,
+    secret_data: &mut BTreeMap<String, String>,
+) -> Result<(), Error> {
+    let server_ref = &user.spec.server_reference;
+
+    let server_name = server_ref.name.as_ref().ok_or(Error::UserInput(
+        "Generated passwords require the server to be referenced by name".to_string(),
+    ))?;
+    let server_namespace = server_ref.namespace.as_deref().unwrap_or(namespace);
+
+    let server_api: Api<SftpgoServer> =
+        Api::namespaced(context.kubernetes_client.clone(), server_namespace);
+
+    let server = server_api
+        .get_opt(server_name)
+        .await?
+        .ok_or_else(|| Error::NotReady(server_name.to_string()))?;
+
+    let configuration = server.spec.configuration.as_ref();
+
+    let sftp_ports = get_binding_ports(
+        configuration
+            .and_then(|c| c.sftpd.as_ref())
+            .and_then(|s| s.bindings.as_ref()),
+        |b| b.port.map(i64::from).unwrap_or(DEFAULT_SFTP_PORT),
+    );
+
+    let ftp_ports = get_binding_ports(
+        configuration
+            .and_then(|c| c.ftpd.as_ref())
+            .and_then(|f| f.bindings.as_ref()),
+        |b| b.port.map(i64::from).unwrap_or(DEFAULT_FTP_PORT),
+    );
+
+    // Without bindings the web admin and REST API are served on the default port
+    let http_ports = match configuration
+        .and_then(|c| c.httpd.as_ref())
+        .and_then(|h| h.bindings.as_ref())
+        .filter(|b| !b.is_empty())
+    {
+        Some(bindings) => get_binding_ports(Some(bindings), |b| {
+            b.port.map(i64::from).unwrap_or(DEFAULT_HTTP_PORT)
+        }),
+        None => vec![DEFAULT_HTTP_PORT],
+    };
+
+    secret_data.insert(
+        SECRET_KEY_HOST.to_string(),
+        format!("{}.{}.svc", server_name, server_namespace),
+    );
+
+    if let Some(sftp_port) = sftp_ports.first() {
+        secret_data.insert(SECRET_KEY_PORT.to_string(), sftp_port.to_string());
+    }
+
+    insert_ports(secret_data, "sftp", &sftp_ports);
+    insert_ports(secret_data, "ftp", &ftp_ports);
+    insert_ports(secret_data, "http", &http_ports);
+
+    Ok(())
+}
+
+/// The ports of the bindings of a protocol, leaving out disabled bindings, which have port 0
+fn get_binding_ports<TBinding>(
+    bindings: Option<&Vec<TBinding>>,
+    get_port: impl Fn(&TBinding) -> i64,
+) -> Vec<i64> {
+    bindings
+        .into_iter()
+        .flatten()
+        .map(get_port)
+        .filter(|port| *port > 0)
+        .collect()
+}
+
+fn insert_ports(secret_data: &mut BTreeMap<String, String>, protocol: &str, ports: &[i64]) {
+    for (index, port) in ports.iter().enumerate() {
+        let key = if index == 0 {
+            format!("{}-port", protocol)
+        } else {
+            format!("{}-port-{}", protocol, index)
+        };
+
+        secret_data.insert(key, port.to_string());
+    }
+}
@@ -1,21 +1,43 @@
+use crate::conditions::{remove_condition, set_condition};
+use crate::consts::SECRET_KEY_PASSWORD;
 use crate::default;
-use crate::filesystem::calculate_file_system;
+use crate::filesystem::{
+    calculate_file_system, check_passphrase_change, get_passphrase_hash, has_stored_files,
+};
 use crate::reconciler::{ContextData, Error, SftpgoResource};
+use crate::role_reconciler::get_role_name;
+use crate::secret_hash::hash_secret;
+use crate::sftpgo_multi_client::get_authorized_client;
+use crate::user_binding::{ensure_binding_secret, get_binding_secret_name};
+use crate::value_from::{get_provided_value, get_value_from};
 use async_trait::async_trait;
 use crds::{
-    ServerReference, SftpgoFolder, SftpgoStatus, SftpgoUser, SftpgoUserConfiguration,
-    SftpgoUserStatus, UserPermission,
+    DenyPolicy, DirectoryPermission, ExpiryPolicy, FieldManagement, GroupReference,
+    ReconcileStatus, RequestStatus, ServerReference, SftpgoFolder, SftpgoGroup, SftpgoGroupType,
+    SftpgoStatus, SftpgoUser, SftpgoUserBinding, SftpgoUserFilters, SftpgoUserResourceStatus,
+    SftpgoUserStatus, SftpgoUserUsage, UserPermission, ValueFrom,
 };
-use kube::Api;
-use sftpgo_client::{UserRequest, UserResponse, UserStatus};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use k8s_openapi::chrono::{self, TimeZone, Utc};
+use kube::{Api, ResourceExt};
+use sftpgo_client::connections::ConnectionClient;
+use sftpgo_client::groups::{GroupMapping, GroupType};
+use sftpgo_client::users::{BandwidthLimit, PatternsDenyPolicy, PatternsFilter};
+use sftpgo_client::{UserFilters, UserRequest, UserResponse, UserStatus};
 use std::collections::HashMap;
+use std::time::Duration;
+
+/// How often the usage and remaining time of users are refreshed in the status
+const STATUS_REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+
+const QUOTA_THRESHOLD_CONDITION: &str = "QuotaThresholdExceeded";
 
-fn calculate_permissions(
-    user_configuration: &SftpgoUserConfiguration,
+pub fn calculate_permissions(
+    global_permissions: &[UserPermission],
+    per_directory_permissions: Option<&Vec<DirectoryPermission>>,
 ) -> HashMap<String, Vec<String>> {
     let mut permissions = HashMap::<String, Vec<String>>::new();
-    let mut root_permissions: Vec<String> = user_configuration
-        .global_permissions
+    let mut root_permissions: Vec<String> = global_permissions
         .iter()
         .map(|permission| permission.to_string())
         .collect();
@@ -26,7 +48,7 @@ fn calculate_permissions(
 
     permissions.insert("/".to_string(), root_permissions);
 
-    if let Some(dir_permissions) = &user_configuration.per_directory_permissions {
+    if let Some(dir_permissions) = per_directory_permissions {
         for dir_permission in dir_permissions.iter() {
             let path_permissions = dir_permission
                 .permissions
@@ -40,12 +62,12 @@ fn calculate_permissions(
     permissions
 }
 
-async fn get_virtual_folder_reference(
-    namespace: &String,
+pub async fn get_virtual_folder_reference(
+    namespace: &str,
     crd_ref: &crds::VirtualFolderReference,
     context: &ContextData,
 ) -> Result<sftpgo_client::virtual_folder_reference::VirtualFolderReference, Error> {
-    let ns = crd_ref.namespace.as_ref().unwrap_or(namespace);
+    let ns = crd_ref.namespace.as_deref().unwrap_or(namespace);
 
     let folder_client: Api<SftpgoFolder> = Api::namespaced(context.kubernetes_client.clone(), ns);
 
@@ -76,6 +98,42 @@ async fn get_virtual_folder_reference(
     }
 }
 
+async fn get_group_mapping(
+    namespace: &str,
+    crd_ref: &GroupReference,
+    context: &ContextData,
+) -> Result<GroupMapping, Error> {
+    let ns = crd_ref.namespace.as_deref().unwrap_or(namespace);
+
+    let group_client: Api<SftpgoGroup> = Api::namespaced(context.kubernetes_client.clone(), ns);
+
+    if let Some(group) = group_client.get_opt(&crd_ref.name).await? {
+        if let Some(status) = group.status {
+            if status.get_id().is_none() {
+                return Err(Error::NotReady(crd_ref.name.to_string()));
+            }
+
+            let group_type = match crd_ref.group_type.unwrap_or_default() {
+                SftpgoGroupType::Primary => GroupType::Primary,
+                SftpgoGroupType::Secondary => GroupType::Secondary,
+                SftpgoGroupType::Membership => GroupType::Membership,
+            };
+
+            Ok(GroupMapping {
+                name: status.get_last_name().to_string(),
+                group_type,
+            })
+        } else {
+            Err(Error::NotReady(crd_ref.name.to_string()))
+        }
+    } else {
+        Err(Error::UserInput(format!(
+            "Group {} not found in namespace {}",
+            crd_ref.name, ns
+        )))
+    }
+}
+
 pub trait MapEnabled<To> {
     fn map_enabled(&self) -> To;
 }
@@ -89,6 +147,180 @@ impl MapEnabled<UserStatus> for SftpgoUserStatus {
     }
 }
 
+/// The status to request. Resources are enabled unless configured otherwise, but with partial
+/// field management a status that isn't configured is left as it is in SFTPGo
+pub fn get_enabled_status(
+    enabled: Option<&SftpgoUserStatus>,
+    field_management: FieldManagement,
+) -> Option<UserStatus> {
+    match (enabled, field_management) {
+        (Some(status), _) => Some(status.map_enabled()),
+        (None, FieldManagement::Full) => Some(UserStatus::Enabled),
+        (None, FieldManagement::Partial) => None,
+    }
+}
+
+fn get_password_hash(user: &SftpgoUser, request: &UserRequest) -> Option<String> {
+    request
+        .password
+        .as_ref()
+        .map(|p| hash_secret(&user.uid().unwrap_or_default(), p))
+}
+
+fn to_strings<T: ToString>(values: Option<&Vec<T>>) -> Option<Vec<String>> {
+    values.map(|v| v.iter().map(|value| value.to_string()).collect())
+}
+
+fn calculate_filters(filters: &SftpgoUserFilters) -> UserFilters {
+    UserFilters {
+        allowed_ip: filters.allowed_ip.clone(),
+        denied_ip: filters.denied_ip.clone(),
+        denied_login_methods: to_strings(filters.denied_login_methods.as_ref()),
+        denied_protocols: to_strings(filters.denied_protocols.as_ref()),
+        file_patterns: filters.file_patterns.as_ref().map(|patterns| {
+            patterns
+                .iter()
+                .map(|p| PatternsFilter {
+                    path: p.path.clone(),
+                    allowed_patterns: p.allowed_patterns.clone(),
+                    denied_patterns: p.denied_patterns.clone(),
+                    deny_policy: p.deny_policy.map(|policy| match policy {
+                        DenyPolicy::Default => PatternsDenyPolicy::Default,
+                        DenyPolicy::Hide => PatternsDenyPolicy::Hide,
+                    }),
+                })
+                .collect()
+        }),
+        max_upload_file_size: filters.max_upload_file_size,
+        tls_username: filters.tls_username.map(|t| t.to_string()),
+        start_directory: filters.start_directory.clone(),
+        two_factor_protocols: to_strings(filters.two_factor_protocols.as_ref()),
+        bandwidth_limits: filters.bandwidth_limits.as_ref().map(|limits| {
+            limits
+                .iter()
+                .map(|l| BandwidthLimit {
+                    sources: l.sources.clone(),
+                    upload_bandwidth: l.upload_bandwidth,
+                    download_bandwidth: l.download_bandwidth,
+                })
+                .collect()
+        }),
+    }
+}
+
+fn get_remaining_time(user: &SftpgoUser) -> Option<chrono::Duration> {
+    user.spec
+        .configuration
+        .expires_at
+        .as_ref()
+        .map(|t| t.0 - Utc::now())
+}
+
+fn is_expired(user: &SftpgoUser) -> bool {
+    get_remaining_time(user).is_some_and(|r| r <= chrono::Duration::zero())
+}
+
+fn format_remaining_time(remaining: chrono::Duration) -> String {
+    let minutes = remaining.num_minutes().max(0);
+    let (days, hours, minutes) = (minutes / (24 * 60), minutes / 60 % 24, minutes % 60);
+
+    if days > 0 {
+        format!("{}d{}h{}m", days, hours, minutes)
+    } else if hours > 0 {
+        format!("{}h{}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+/// Converts a timestamp in milliseconds from SFTPGo, where 0 means never
+fn to_time(timestamp: Option<i64>) -> Option<Time> {
+    timestamp
+        .filter(|t| *t > 0)
+        .and_then(|t| Utc.timestamp_millis_opt(t).single())
+        .map(Time)
+}
+
+/// The highest usage of the quota size and files in percent, if the user has a quota
+fn get_quota_usage_percentage(response: &UserResponse) -> Option<i64> {
+    let size = response
+        .quota_size
+        .filter(|q| *q > 0)
+        .map(|q| response.used_quota_size.unwrap_or_default() * 100 / q);
+
+    let files = response
+        .quota_files
+        .filter(|q| *q > 0)
+        .map(|q| i64::from(response.used_quota_files.unwrap_or_default()) * 100 / i64::from(q));
+
+    size.max(files)
+}
+
+/// Gets the password of the user, either given directly or read from the referenced or generated
+/// secret
+pub async fn get_user_password(
+    user: &SftpgoUser,
+    context: &ContextData,
+    namespace: &str,
+) -> Result<Option<String>, Error> {
+    let configuration = &user.spec.configuration;
+
+    let generate_password = configuration.generate_password.unwrap_or(false);
+
+    let value_from = match (
+        &configuration.password,
+        &configuration.password_from,
+        generate_password,
+    ) {
+        (None, None, false) => return Ok(None),
+        (Some(password), None, false) => return Ok(Some(password.clone())),
+        (None, Some(password_from), false) => ValueFrom::SecretKeyRef {
+            name: password_from.name.clone(),
+            key: password_from.key.clone(),
+        },
+        (None, None, true) => ValueFrom::SecretKeyRef {
+            name: get_binding_secret_name(user),
+            key: SECRET_KEY_PASSWORD.to_string(),
+        },
+        _ => {
+            return Err(Error::UserInput(format!(
+                "User {} can only have one of password, passwordFrom and generatePassword",
+                configuration.username
+            )))
+        }
+    };
+
+    Ok(Some(get_value_from(&value_from, context, namespace).await?))
+}
+
+/// Gets the public keys of the user. Values read from secrets and config maps can contain
+/// multiple keys, one per line.
+async fn get_public_keys(
+    user: &SftpgoUser,
+    context: &ContextData,
+    namespace: &str,
+) -> Result<Option<Vec<String>>, Error> {
+    let Some(provided_keys) = &user.spec.configuration.public_keys else {
+        return Ok(None);
+    };
+
+    let mut public_keys = vec![];
+
+    for provided_key in provided_keys {
+        let value = get_provided_value(provided_key, context, namespace).await?;
+
+        public_keys.extend(
+            value
+                .lines()
+                .map(|line| line.trim())
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(|line| line.to_string()),
+        );
+    }
+
+    Ok(Some(public_keys))
+}
+
 #[async_trait]
 impl SftpgoResource for SftpgoUser {
     type Request = UserRequest;
@@ -101,11 +333,18 @@ impl SftpgoResource for SftpgoUser {
     async fn get_request(
         &self,
         context: &ContextData,
-        namespace: &String,
+        namespace: &str,
     ) -> Result<Self::Request, Error> {
         let user_configuration = &self.spec.configuration;
 
-        let permissions = calculate_permissions(user_configuration);
+        if user_configuration.generate_password.unwrap_or(false) {
+            ensure_binding_secret(self, context, namespace).await?;
+        }
+
+        let permissions = calculate_permissions(
+            &user_configuration.global_permissions,
+            user_configuration.per_directory_permissions.as_ref(),
+        );
 
         let virtual_folders = if let Some(folders) = &self.spec.configuration.virtual_folders {
             let mut references = Vec::new();
@@ -120,16 +359,95 @@ impl SftpgoResource for SftpgoUser {
             None
         };
 
+        let groups = if let Some(groups) = &self.spec.configuration.groups {
+            let primary_groups = groups
+                .iter()
+                .filter(|g| g.group_type.unwrap_or_default() == SftpgoGroupType::Primary)
+                .count();
+
+            if primary_groups > 1 {
+                return Err(Error::UserInput(format!(
+                    "User {} has {} primary groups, at most one is allowed",
+                    user_configuration.username, primary_groups
+                )));
+            }
+
+            let mut mappings = Vec::new();
+
+            for group in groups {
+                let mapping = get_group_mapping(namespace, group, context).await?;
+                mappings.push(mapping);
+            }
+
+            Some(mappings)
+        } else {
+            None
+        };
+
+        let role = if let Some(reference) = &user_configuration.role_reference {
+            Some(get_role_name(namespace, reference, context).await?)
+        } else {
+            None
+        };
+
+        let password = get_user_password(self, context, namespace).await?;
+        let public_keys = get_public_keys(self, context, namespace).await?;
+
+        let has_public_keys = public_keys.as_ref().is_some_and(|k| !k.is_empty());
+
+        if password.is_none() && !has_public_keys {
+            return Err(Error::UserInput(format!(
+                "User {} needs either a password or public keys",
+                user_configuration.username
+            )));
+        }
+
+        let filters = user_configuration.filters.as_ref().map(calculate_filters);
+
+        // Without a filesystem in the configuration, partial field management keeps the one in
+        // SFTPGo, rather than switching it to the local filesystem
+        let filesystem = if user_configuration.filesystem.is_some()
+            || self.get_field_management() == FieldManagement::Full
+        {
+            Some(
+                calculate_file_system(user_configuration.filesystem.as_ref(), context, namespace)
+                    .await?,
+            )
+        } else {
+            None
+        };
+
         let user_request = UserRequest {
             username: user_configuration.username.clone(),
-            password: Some(user_configuration.password.clone()),
-            status: user_configuration
-                .enabled
-                .map_or(UserStatus::Enabled, |status| status.map_enabled()),
+            password,
+            public_keys,
+            status: if is_expired(self) && self.spec.on_expiry == Some(ExpiryPolicy::Disable) {
+                Some(UserStatus::Disabled)
+            } else {
+                get_enabled_status(
+                    user_configuration.enabled.as_ref(),
+                    self.get_field_management(),
+                )
+            },
+            expiration_date: user_configuration
+                .expires_at
+                .as_ref()
+                .map(|t| t.0.timestamp_millis()),
             permissions: permissions.clone(),
             home_dir: user_configuration.home_dir.clone(),
-            filesystem: calculate_file_system(user_configuration.filesystem.as_ref()).await?,
+            filesystem,
             virtual_folders,
+            groups,
+            role,
+            max_sessions: user_configuration.max_sessions,
+            quota_size: user_configuration.quota_size,
+            quota_files: user_configuration.quota_files,
+            upload_bandwidth: user_configuration.upload_bandwidth,
+            download_bandwidth: user_configuration.download_bandwidth,
+            upload_data_transfer: user_configuration.upload_data_transfer,
+            download_data_transfer: user_configuration.download_data_transfer,
+            total_data_transfer: user_configuration.total_data_transfer,
+            filters,
             ..default()
         };
 
@@ -139,4 +457,158 @@ impl SftpgoResource for SftpgoUser {
     fn get_server_reference(&self) -> &ServerReference {
         &self.spec.server_reference
     }
+
+    fn get_field_management(&self) -> FieldManagement {
+        self.spec.field_management.unwrap_or_default()
+    }
+
+    fn validate_update(
+        &self,
+        existing: &Self::Response,
+        request: &Self::Request,
+    ) -> Result<(), Error> {
+        let Some(filesystem) = &request.filesystem else {
+            return Ok(());
+        };
+
+        check_passphrase_change(
+            &request.username,
+            self.status
+                .as_ref()
+                .and_then(|s| s.passphrase_hash.as_deref()),
+            filesystem,
+            &self.uid().unwrap_or_default(),
+            self.spec.allow_passphrase_change.unwrap_or(false),
+            has_stored_files(existing.used_quota_size, existing.used_quota_files),
+        )
+    }
+
+    fn prepare_update(&self, request: &mut Self::Request) {
+        let previous_hash = self.status.as_ref().and_then(|s| s.password_hash.as_ref());
+
+        // SFTPGo only stores a hash of the password, so it is only sent when it changed to
+        // avoid rehashing it on every update
+        if previous_hash.is_some() && previous_hash == get_password_hash(self, request).as_ref() {
+            request.password = None;
+        }
+    }
+
+    fn update_status(&self, request: &Self::Request, status: &mut SftpgoUserResourceStatus) {
+        status.passphrase_hash = request
+            .filesystem
+            .as_ref()
+            .and_then(|f| get_passphrase_hash(f, &self.uid().unwrap_or_default()));
+        status.password_hash = get_password_hash(self, request);
+        status.binding = self
+            .spec
+            .configuration
+            .generate_password
+            .unwrap_or(false)
+            .then(|| SftpgoUserBinding {
+                name: get_binding_secret_name(self),
+            });
+        status.expired = is_expired(self);
+        status.remaining_time = get_remaining_time(self)
+            .filter(|_| !status.expired)
+            .map(format_remaining_time);
+    }
+
+    async fn after_update(
+        &self,
+        context: &ContextData,
+        namespace: &str,
+        status: &SftpgoUserResourceStatus,
+    ) -> Result<(), Error> {
+        // Without a previous hash, the user was last reconciled before hashes were recorded, so
+        // it's unknown whether anything changed
+        let Some(previous_hash) = self.status.as_ref().and_then(|s| s.get_request_hash()) else {
+            return Ok(());
+        };
+
+        if !self.spec.disconnect_on_change.unwrap_or(false)
+            || Some(previous_hash) == status.get_request_hash()
+        {
+            return Ok(());
+        }
+
+        let client = get_authorized_client(&self.spec.server_reference, context, namespace).await?;
+        let username = &self.spec.configuration.username;
+        let closed = client.close_user_connections(username).await?;
+
+        info!(
+            "Closed {} connections of user {} after it changed",
+            closed, username
+        );
+
+        Ok(())
+    }
+
+    fn update_status_from_response(
+        &self,
+        response: &UserResponse,
+        status: &mut SftpgoUserResourceStatus,
+    ) {
+        if let Some(threshold) = self.spec.quota_warning_threshold {
+            let (exceeded, reason, message) = match get_quota_usage_percentage(response) {
+                Some(percentage) if percentage >= i64::from(threshold) => (
+                    true,
+                    "ThresholdExceeded",
+                    format!("{}% of the quota is used", percentage),
+                ),
+                Some(percentage) => (
+                    false,
+                    "BelowThreshold",
+                    format!("{}% of the quota is used", percentage),
+                ),
+                None => (false, "NoQuota", "The user has no quota".to_string()),
+            };
+
+            set_condition(
+                status.conditions_mut(),
+                QUOTA_THRESHOLD_CONDITION,
+                exceeded,
+                reason,
+                message,
+                self.metadata.generation,
+            );
+        } else {
+            remove_condition(status.conditions_mut(), QUOTA_THRESHOLD_CONDITION);
+        }
+
+        status.usage = Some(SftpgoUserUsage {
+            used_quota_size: response.used_quota_size.unwrap_or_default(),
+            used_quota_files: response.used_quota_files.unwrap_or_default(),
+            used_upload_data_transfer: response.used_upload_data_transfer.unwrap_or_default(),
+            used_download_data_transfer: response.used_download_data_transfer.unwrap_or_default(),
+            last_login: to_time(response.last_login),
+            first_upload: to_time(response.first_upload),
+        });
+    }
+
+    fn get_requeue_after(&self) -> Option<Duration> {
+        // Refresh the status regularly, and run again right after the account expired to apply
+        // the expiry policy
+        let until_expiry = get_remaining_time(self)
+            .and_then(|r| r.to_std().ok())
+            .map(|r| r + Duration::from_secs(1));
+
+        Some(until_expiry.map_or(STATUS_REFRESH_INTERVAL, |r| r.min(STATUS_REFRESH_INTERVAL)))
+    }
+
+    fn should_delete(&self) -> bool {
+        is_expired(self) && self.spec.on_expiry == Some(ExpiryPolicy::Delete)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remaining_time_is_formatted_in_days_hours_and_minutes() {
+        let remaining = chrono::Duration::minutes(2 * 24 * 60 + 3 * 60 + 4);
+        assert_eq!(format_remaining_time(remaining), "2d3h4m");
+        assert_eq!(format_remaining_time(chrono::Duration::minutes(65)), "1h5m");
+        assert_eq!(format_remaining_time(chrono::Duration::seconds(-30)), "0m");
+    }
 }
@@ -0,0 +1,77 @@
+use crate::reconciler::{ContextData, Error};
+use crds::{ProvidedValue, ValueFrom};
+use k8s_openapi::api::core::v1::{ConfigMap, Secret};
+use kube::Api;
+
+/// Reads the referenced key from a secret or config map in the given namespace
+pub async fn get_value_from(
+    value_from: &ValueFrom,
+    context: &ContextData,
+    namespace: &str,
+) -> Result<String, Error> {
+    match value_from {
+        ValueFrom::SecretKeyRef { name, key } => {
+            let api: Api<Secret> = Api::namespaced(context.kubernetes_client.clone(), namespace);
+
+            let secret = api.get_opt(name).await?.ok_or_else(|| {
+                Error::UserInput(format!(
+                    "Secret {} not found in namespace {}",
+                    name, namespace
+                ))
+            })?;
+
+            let value = secret
+                .data
+                .as_ref()
+                .and_then(|d| d.get(key))
+                .ok_or_else(|| {
+                    Error::UserInput(format!(
+                        "Secret {} does not contain the key '{}'",
+                        name, key
+                    ))
+                })?;
+
+            String::from_utf8(value.0.clone()).map_err(|e| {
+                Error::UserInput(format!(
+                    "Secret {} contains invalid UTF-8 in key '{}': {}",
+                    name, key, e
+                ))
+            })
+        }
+        ValueFrom::ConfigMapKeyRef { name, key } => {
+            let api: Api<ConfigMap> = Api::namespaced(context.kubernetes_client.clone(), namespace);
+
+            let config_map = api.get_opt(name).await?.ok_or_else(|| {
+                Error::UserInput(format!(
+                    "ConfigMap {} not found in namespace {}",
+                    name, namespace
+                ))
+            })?;
+
+            config_map
+                .data
+                .as_ref()
+                .and_then(|d| d.get(key))
+                .cloned()
+                .ok_or_else(|| {
+                    Error::UserInput(format!(
+                        "ConfigMap {} does not contain the key '{}'",
+                        name, key
+                    ))
+                })
+        }
+    }
+}
+
+pub async fn get_provided_value(
+    value: &ProvidedValue<String>,
+    context: &ContextData,
+    namespace: &str,
+) -> Result<String, Error> {
+    match value {
+        ProvidedValue::Value(value) => Ok(value.clone()),
+        ProvidedValue::ValueFrom(value_from) => {
+            get_value_from(value_from, context, namespace).await
+        }
+    }
+}
@@ -143,7 +143,7 @@ impl ViperEnvironmentSerializer {
     }
 }
 
-impl<'a> ser::Serializer for &'a mut ViperEnvironmentSerializer {
+impl ser::Serializer for &mut ViperEnvironmentSerializer {
     type Ok = ();
     type Error = ViperEnvironmentSerializerError;
     type SerializeSeq = Self;
@@ -227,9 +227,9 @@ impl<'a> ser::Serializer for &'a mut ViperEnvironmentSerializer {
         Ok(())
     }
 
-    fn serialize_some<T: ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error>
+    fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         value.serialize(self)
     }
@@ -252,18 +252,18 @@ impl<'a> ser::Serializer for &'a mut ViperEnvironmentSerializer {
         Ok(())
     }
 
-    fn serialize_newtype_struct<T: ?Sized>(
+    fn serialize_newtype_struct<T>(
         self,
         _name: &'static str,
         _value: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         todo!()
     }
 
-    fn serialize_newtype_variant<T: ?Sized>(
+    fn serialize_newtype_variant<T>(
         self,
         _name: &'static str,
         _variant_index: u32,
@@ -271,7 +271,7 @@ impl<'a> ser::Serializer for &'a mut ViperEnvironmentSerializer {
         _value: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         todo!()
     }
@@ -327,13 +327,13 @@ impl<'a> ser::Serializer for &'a mut ViperEnvironmentSerializer {
     }
 }
 
-impl<'a> ser::SerializeSeq for &'a mut ViperEnvironmentSerializer {
+impl ser::SerializeSeq for &mut ViperEnvironmentSerializer {
     type Ok = ();
     type Error = ViperEnvironmentSerializerError;
 
-    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         value.serialize(&mut **self)?;
         let tail = self.field_stack.pop();
@@ -364,13 +364,13 @@ impl<'a> ser::SerializeSeq for &'a mut ViperEnvironmentSerializer {
     }
 }
 
-impl<'a> ser::SerializeTuple for &'a mut ViperEnvironmentSerializer {
+impl ser::SerializeTuple for &mut ViperEnvironmentSerializer {
     type Ok = ();
     type Error = ViperEnvironmentSerializerError;
 
-    fn serialize_element<T: ?Sized>(&mut self, _value: &T) -> Result<(), Self::Error>
+    fn serialize_element<T>(&mut self, _value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         todo!()
     }
@@ -380,13 +380,13 @@ impl<'a> ser::SerializeTuple for &'a mut ViperEnvironmentSerializer {
     }
 }
 
-impl<'a> ser::SerializeTupleStruct for &'a mut ViperEnvironmentSerializer {
+impl ser::SerializeTupleStruct for &mut ViperEnvironmentSerializer {
     type Ok = ();
     type Error = ViperEnvironmentSerializerError;
 
-    fn serialize_field<T: ?Sized>(&mut self, _value: &T) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, _value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         todo!()
     }
@@ -396,13 +396,13 @@ impl<'a> ser::SerializeTupleStruct for &'a mut ViperEnvironmentSerializer {
     }
 }
 
-impl<'a> ser::SerializeTupleVariant for &'a mut ViperEnvironmentSerializer {
+impl ser::SerializeTupleVariant for &mut ViperEnvironmentSerializer {
     type Ok = ();
     type Error = ViperEnvironmentSerializerError;
 
-    fn serialize_field<T: ?Sized>(&mut self, _value: &T) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, _value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         todo!()
     }
@@ -412,32 +412,28 @@ impl<'a> ser::SerializeTupleVariant for &'a mut ViperEnvironmentSerializer {
     }
 }
 
-impl<'a> ser::SerializeMap for &'a mut ViperEnvironmentSerializer {
+impl ser::SerializeMap for &mut ViperEnvironmentSerializer {
     type Ok = ();
     type Error = ViperEnvironmentSerializerError;
 
-    fn serialize_key<T: ?Sized>(&mut self, _key: &T) -> Result<(), Self::Error>
+    fn serialize_key<T>(&mut self, _key: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         todo!()
     }
 
-    fn serialize_value<T: ?Sized>(&mut self, _value: &T) -> Result<(), Self::Error>
+    fn serialize_value<T>(&mut self, _value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         todo!()
     }
 
-    fn serialize_entry<K: ?Sized, V: ?Sized>(
-        &mut self,
-        _key: &K,
-        _value: &V,
-    ) -> Result<(), Self::Error>
+    fn serialize_entry<K, V>(&mut self, _key: &K, _value: &V) -> Result<(), Self::Error>
     where
-        K: Serialize,
-        V: Serialize,
+        K: ?Sized + Serialize,
+        V: ?Sized + Serialize,
     {
         todo!()
     }
@@ -447,17 +443,13 @@ impl<'a> ser::SerializeMap for &'a mut ViperEnvironmentSerializer {
     }
 }
 
-impl<'a> ser::SerializeStruct for &'a mut ViperEnvironmentSerializer {
+impl ser::SerializeStruct for &mut ViperEnvironmentSerializer {
     type Ok = ();
     type Error = ViperEnvironmentSerializerError;
 
-    fn serialize_field<T: ?Sized>(
-        &mut self,
-        key: &'static str,
-        value: &T,
-    ) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         self.field_stack
             .push(ViperEnvironmentSerializerStackElement::String(
@@ -486,17 +478,13 @@ impl<'a> ser::SerializeStruct for &'a mut ViperEnvironmentSerializer {
     }
 }
 
-impl<'a> ser::SerializeStructVariant for &'a mut ViperEnvironmentSerializer {
+impl ser::SerializeStructVariant for &mut ViperEnvironmentSerializer {
     type Ok = ();
     type Error = ViperEnvironmentSerializerError;
 
-    fn serialize_field<T: ?Sized>(
-        &mut self,
-        _key: &'static str,
-        _value: &T,
-    ) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, _key: &'static str, _value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         todo!()
     }
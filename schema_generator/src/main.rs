@@ -1,4 +1,7 @@
-use crds::{SftpgoAdmin, SftpgoFolder, SftpgoServer, SftpgoUser};
+use crds::{
+    SftpgoAdmin, SftpgoApiKey, SftpgoEventAction, SftpgoEventRule, SftpgoFolder, SftpgoGroup,
+    SftpgoIpListEntry, SftpgoRole, SftpgoServer, SftpgoShare, SftpgoUser,
+};
 use kube::CustomResourceExt;
 use std::fs::File;
 use std::io::Write;
@@ -12,6 +15,13 @@ fn main() {
     write_crd::<SftpgoUser>(&mut file);
     write_crd::<SftpgoFolder>(&mut file);
     write_crd::<SftpgoAdmin>(&mut file);
+    write_crd::<SftpgoGroup>(&mut file);
+    write_crd::<SftpgoRole>(&mut file);
+    write_crd::<SftpgoApiKey>(&mut file);
+    write_crd::<SftpgoEventAction>(&mut file);
+    write_crd::<SftpgoEventRule>(&mut file);
+    write_crd::<SftpgoShare>(&mut file);
+    write_crd::<SftpgoIpListEntry>(&mut file);
 }
 
 fn write_crd<TResource: CustomResourceExt>(mut file: &mut File) {
@@ -11,7 +11,7 @@ pub struct AdminRequest {
     pub password: String,
     pub email: Option<String>,
     pub permissions: Vec<String>,
-    pub status: UserStatus,
+    pub status: Option<UserStatus>,
     pub role: Option<String>,
 }
 
@@ -0,0 +1,33 @@
+use crate::auth::AuthContext;
+use crate::error_response::Result;
+use async_trait::async_trait;
+use reqwest::header::HeaderName;
+use std::sync::Arc;
+
+/// Authenticates using an api key sent in the X-SFTPGO-API-KEY header instead of username and
+/// password. For admin keys the associated admin must have api key authentication enabled. Keys
+/// not associated with an admin can be used to impersonate an admin by appending "." and the
+/// username to the key.
+#[derive(Clone)]
+pub struct ApiKeyAuthContext {
+    api_key: Arc<String>,
+}
+
+impl ApiKeyAuthContext {
+    pub fn new(api_key: String) -> ApiKeyAuthContext {
+        ApiKeyAuthContext {
+            api_key: Arc::new(api_key),
+        }
+    }
+}
+
+#[async_trait]
+impl AuthContext for ApiKeyAuthContext {
+    async fn get_auth_header_value(&self) -> Result<String> {
+        Ok(self.api_key.to_string())
+    }
+
+    fn get_auth_header_name(&self) -> HeaderName {
+        HeaderName::from_static("x-sftpgo-api-key")
+    }
+}
@@ -0,0 +1,141 @@
+use crate::error_response::{handle_response, Result};
+use crate::{AuthorizedSftpgoClientBase, GenericResponseBody};
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[repr(u8)]
+pub enum ApiKeyScope {
+    #[default]
+    Admin = 1,
+    User = 2,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct ApiKeyRequest {
+    pub name: String,
+    pub scope: ApiKeyScope,
+    /// expiration time as unix timestamp in milliseconds. 0 means no expiration
+    pub expires_at: Option<i64>,
+    pub description: Option<String>,
+    /// username of the user associated with this key, only valid for user scope
+    pub user: Option<String>,
+    /// username of the admin associated with this key, only valid for admin scope
+    pub admin: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct ApiKeyResponse {
+    pub id: String,
+    pub name: String,
+    pub scope: ApiKeyScope,
+    pub expires_at: Option<i64>,
+    pub last_use_at: Option<i64>,
+    pub description: Option<String>,
+    pub user: Option<String>,
+    pub admin: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CreatedApiKeyBody {
+    key: String,
+}
+
+/// A newly created api key. The key itself is only returned by the server on creation, so
+/// make sure to store it.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CreatedApiKey {
+    pub id: String,
+    pub key: String,
+}
+
+/// Api keys are identified by their generated id rather than their name, so they don't fit
+/// the generic rest client.
+#[async_trait]
+pub trait ApiKeyClient: AuthorizedSftpgoClientBase {
+    async fn create_api_key(&self, item: &ApiKeyRequest) -> Result<CreatedApiKey> {
+        let url = self.url_for("/api/v2/apikeys")?;
+
+        let auth_context = self.get_auth_context();
+        let auth_header_value = auth_context.get_auth_header_value().await?;
+        let res = self
+            .get_client()
+            .post(url)
+            .header(auth_context.get_auth_header_name(), auth_header_value)
+            .json(&item)
+            .send()
+            .await?;
+
+        let id = res
+            .headers()
+            .get("X-Object-ID")
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string());
+
+        let body: CreatedApiKeyBody = handle_response(res).await?;
+
+        // The key is formatted as "<id>.<secret>", so the id can be recovered if the header is
+        // missing
+        let id = id.unwrap_or_else(|| body.key.split('.').next().unwrap_or_default().to_string());
+
+        Ok(CreatedApiKey { id, key: body.key })
+    }
+
+    async fn update_api_key(&self, id: &str, item: &ApiKeyRequest) -> Result<GenericResponseBody> {
+        let url = self.url_for(&format!("/api/v2/apikeys/{}", id))?;
+
+        let auth_context = self.get_auth_context();
+        let auth_header_value = auth_context.get_auth_header_value().await?;
+        let res = self
+            .get_client()
+            .put(url)
+            .header(auth_context.get_auth_header_name(), auth_header_value)
+            .json(&item)
+            .send()
+            .await?;
+
+        handle_response(res).await
+    }
+
+    async fn get_api_key(&self, id: &str) -> Result<Option<ApiKeyResponse>> {
+        let url = self.url_for(&format!("/api/v2/apikeys/{}", id))?;
+
+        let auth_context = self.get_auth_context();
+        let auth_header_value = auth_context.get_auth_header_value().await?;
+        let res = self
+            .get_client()
+            .get(url)
+            .header(auth_context.get_auth_header_name(), auth_header_value)
+            .send()
+            .await?;
+
+        if res.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        handle_response(res).await
+    }
+
+    async fn delete_api_key(&self, id: &str) -> Result<()> {
+        let url = self.url_for(&format!("/api/v2/apikeys/{}", id))?;
+
+        let auth_context = self.get_auth_context();
+        let auth_header_value = auth_context.get_auth_header_value().await?;
+        let res = self
+            .get_client()
+            .delete(url)
+            .header(auth_context.get_auth_header_name(), auth_header_value)
+            .send()
+            .await?;
+
+        if res.status() == StatusCode::NOT_FOUND || res.status() == StatusCode::OK {
+            return Ok(());
+        }
+
+        handle_response(res).await
+    }
+}
+
+impl<T> ApiKeyClient for T where T: AuthorizedSftpgoClientBase {}
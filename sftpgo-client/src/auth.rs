@@ -2,6 +2,8 @@ use crate::error_response::Result;
 use async_trait::async_trait;
 use base64::engine::general_purpose;
 use base64::Engine;
+use reqwest::header::{HeaderName, AUTHORIZATION};
+use std::sync::Arc;
 
 pub fn create_basic_auth_header(username: &str, password: &str) -> String {
     let encoded = general_purpose::STANDARD.encode(format!("{}:{}", username, password));
@@ -15,4 +17,22 @@ pub fn create_bearer_auth_header(token: &str) -> String {
 #[async_trait]
 pub trait AuthContext: Sync + Send {
     async fn get_auth_header_value(&self) -> Result<String>;
+
+    fn get_auth_header_name(&self) -> HeaderName {
+        AUTHORIZATION
+    }
+}
+
+#[async_trait]
+impl<T> AuthContext for Arc<T>
+where
+    T: AuthContext + ?Sized,
+{
+    async fn get_auth_header_value(&self) -> Result<String> {
+        self.as_ref().get_auth_header_value().await
+    }
+
+    fn get_auth_header_name(&self) -> HeaderName {
+        self.as_ref().get_auth_header_name()
+    }
 }
@@ -87,7 +87,8 @@ mod tests {
 
     #[test]
     fn is_send_and_sync() {
-        use crate::{RefreshableAdminAuthContext, SftpgoClient};
+        use crate::{ApiKeyAuthContext, AuthContext, RefreshableAdminAuthContext, SftpgoClient};
+        use std::sync::Arc;
 
         fn is_send<T: Send>() {}
         fn is_sync<T: Sync>() {}
@@ -95,5 +96,9 @@ mod tests {
         is_sync::<SftpgoClient>();
         is_send::<AuthorizedSftpgoClient<RefreshableAdminAuthContext<SftpgoClient>>>();
         is_sync::<AuthorizedSftpgoClient<RefreshableAdminAuthContext<SftpgoClient>>>();
+        is_send::<AuthorizedSftpgoClient<ApiKeyAuthContext>>();
+        is_sync::<AuthorizedSftpgoClient<ApiKeyAuthContext>>();
+        is_send::<AuthorizedSftpgoClient<Arc<dyn AuthContext>>>();
+        is_sync::<AuthorizedSftpgoClient<Arc<dyn AuthContext>>>();
     }
 }
@@ -0,0 +1,83 @@
+use crate::error_response::{handle_response, Result};
+use crate::{AuthorizedSftpgoClientBase, GenericResponseBody};
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct ConnectionStatus {
+    pub username: String,
+    pub connection_id: String,
+    pub client_version: Option<String>,
+    pub remote_address: Option<String>,
+    /// unix timestamp in milliseconds
+    pub connection_time: Option<i64>,
+    /// unix timestamp in milliseconds
+    pub last_activity: Option<i64>,
+    pub protocol: Option<String>,
+    /// The node handling the connection, when running multiple instances
+    pub node: Option<String>,
+}
+
+/// Active connections are listed and closed by connection id, so they don't fit the generic
+/// rest client.
+#[async_trait]
+pub trait ConnectionClient: AuthorizedSftpgoClientBase {
+    async fn get_connections(&self) -> Result<Vec<ConnectionStatus>> {
+        let url = self.url_for("/api/v2/connections")?;
+
+        let auth_context = self.get_auth_context();
+        let auth_header_value = auth_context.get_auth_header_value().await?;
+        let res = self
+            .get_client()
+            .get(url)
+            .header(auth_context.get_auth_header_name(), auth_header_value)
+            .send()
+            .await?;
+
+        let connections: Option<Vec<ConnectionStatus>> = handle_response(res).await?;
+
+        Ok(connections.unwrap_or_default())
+    }
+
+    async fn close_connection(&self, connection: &ConnectionStatus) -> Result<()> {
+        let mut url = self.url_for(&format!("/api/v2/connections/{}", connection.connection_id))?;
+
+        if let Some(node) = connection.node.as_deref().filter(|n| !n.is_empty()) {
+            url.query_pairs_mut().append_pair("node", node);
+        }
+
+        let auth_context = self.get_auth_context();
+        let auth_header_value = auth_context.get_auth_header_value().await?;
+        let res = self
+            .get_client()
+            .delete(url)
+            .header(auth_context.get_auth_header_name(), auth_header_value)
+            .send()
+            .await?;
+
+        // The connection might have been closed in the meantime
+        if res.status() == StatusCode::NOT_FOUND || res.status() == StatusCode::OK {
+            return Ok(());
+        }
+
+        handle_response::<GenericResponseBody>(res).await?;
+
+        Ok(())
+    }
+
+    /// Closes all active connections of the user, and returns how many were closed
+    async fn close_user_connections(&self, username: &str) -> Result<usize> {
+        let connections = self.get_connections().await?;
+
+        let mut closed = 0;
+        for connection in connections.iter().filter(|c| c.username == username) {
+            self.close_connection(connection).await?;
+            closed += 1;
+        }
+
+        Ok(closed)
+    }
+}
+
+impl<T> ConnectionClient for T where T: AuthorizedSftpgoClientBase {}
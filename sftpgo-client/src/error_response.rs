@@ -18,8 +18,12 @@ pub enum SftpgoError {
     Unauthorized(GenericResponseBody),
     #[error("Not found: {0:?}")]
     NotFound(GenericResponseBody),
+    #[error("Unexpected status {0}: {1:?}")]
+    UnexpectedStatus(StatusCode, GenericResponseBody),
     #[error("Request error: {0:?}")]
     ReqwestError(#[from] reqwest::Error),
+    #[error("Invalid response: {0}")]
+    InvalidResponse(#[from] serde_json::Error),
     #[error("Url building failed: {0:?}. This is most likely a bug in the code.")]
     UrlBuildingFailed(#[from] url::ParseError),
 }
@@ -28,19 +32,31 @@ pub async fn handle_response<T>(response: Response) -> Result<T>
 where
     T: for<'de> Deserialize<'de>,
 {
-    match response.error_for_status_ref() {
-        Ok(_) => Ok(response.json().await?),
-        Err(err) => match err.status() {
-            Some(StatusCode::UNAUTHORIZED) => {
-                Err(SftpgoError::Unauthorized(response.json().await?))
-            }
-            Some(StatusCode::BAD_REQUEST) => Err(SftpgoError::Unauthorized(response.json().await?)),
-            Some(StatusCode::INTERNAL_SERVER_ERROR) => {
-                Err(SftpgoError::Unauthorized(response.json().await?))
-            }
-            _ => Err(SftpgoError::ReqwestError(err)),
-        },
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response.json().await?);
     }
+
+    let body = read_error_body(response).await?;
+
+    match status {
+        StatusCode::UNAUTHORIZED => Err(SftpgoError::Unauthorized(body)),
+        StatusCode::BAD_REQUEST => Err(SftpgoError::BadRequest(body)),
+        StatusCode::NOT_FOUND => Err(SftpgoError::NotFound(body)),
+        StatusCode::INTERNAL_SERVER_ERROR => Err(SftpgoError::InternalServerError(body)),
+        _ => Err(SftpgoError::UnexpectedStatus(status, body)),
+    }
+}
+
+/// Reads the body of a failed request. SFTPGo usually answers with json, but proxies in front of
+/// it might not, so anything else is kept as the error text.
+async fn read_error_body(response: Response) -> Result<GenericResponseBody> {
+    let text = response.text().await?;
+
+    Ok(serde_json::from_str(&text).unwrap_or(GenericResponseBody {
+        message: None,
+        error: Some(text),
+    }))
 }
 
 pub type Result<T> = std::result::Result<T, SftpgoError>;
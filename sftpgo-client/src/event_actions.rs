@@ -0,0 +1,141 @@
+use crate::client::SftpgoClientBase;
+use crate::filesystem::SftpgoSecret;
+use crate::rest_client::{EasyRestSftpgoClient, Named};
+use crate::{Creates, Existing, Result};
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use url::Url;
+
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[repr(u8)]
+pub enum EventActionType {
+    #[default]
+    Http = 1,
+    Command = 2,
+    Email = 3,
+    Backup = 4,
+    UserQuotaReset = 5,
+    FolderQuotaReset = 6,
+    TransferQuotaReset = 7,
+    DataRetentionCheck = 8,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct KeyValue {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct EventActionHttpConfig {
+    pub endpoint: String,
+    pub username: Option<String>,
+    pub password: Option<SftpgoSecret>,
+    pub headers: Option<Vec<KeyValue>>,
+    /// timeout in seconds
+    pub timeout: i32,
+    pub skip_tls_verify: Option<bool>,
+    pub method: String,
+    pub query_parameters: Option<Vec<KeyValue>>,
+    pub body: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct EventActionCommandConfig {
+    pub cmd: String,
+    pub args: Option<Vec<String>>,
+    /// timeout in seconds
+    pub timeout: i32,
+    pub env_vars: Option<Vec<KeyValue>>,
+}
+
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[repr(u8)]
+pub enum EmailContentType {
+    #[default]
+    TextPlain = 0,
+    TextHtml = 1,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct EventActionEmailConfig {
+    pub recipients: Vec<String>,
+    pub bcc: Option<Vec<String>>,
+    pub subject: String,
+    pub body: String,
+    pub content_type: EmailContentType,
+    pub attachments: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct FolderRetention {
+    pub path: String,
+    /// retention time in hours. 0 means exclude this path
+    pub retention: i32,
+    pub delete_empty_dirs: Option<bool>,
+    pub ignore_user_permissions: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct EventActionDataRetentionConfig {
+    pub folders: Vec<FolderRetention>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct EventActionOptions {
+    pub http_config: Option<EventActionHttpConfig>,
+    pub cmd_config: Option<EventActionCommandConfig>,
+    pub email_config: Option<EventActionEmailConfig>,
+    pub retention_config: Option<EventActionDataRetentionConfig>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct EventActionRequest {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(rename = "type")]
+    pub action_type: EventActionType,
+    pub options: EventActionOptions,
+}
+
+impl Named for EventActionRequest {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Creates<EventActionResponse> for EventActionRequest {}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct EventActionResponse {
+    pub id: i32,
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(rename = "type")]
+    pub action_type: EventActionType,
+    pub options: Option<EventActionOptions>,
+    pub rules: Option<Vec<String>>,
+}
+
+impl Existing for EventActionResponse {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+}
+
+impl<Client> EasyRestSftpgoClient<EventActionRequest, EventActionResponse> for Client
+where
+    Client: SftpgoClientBase,
+{
+    fn get_url(&self, path: Option<&str>) -> Result<Url> {
+        if let Some(path) = path {
+            self.url_for(&format!("/api/v2/eventactions/{}", path))
+        } else {
+            self.url_for("/api/v2/eventactions")
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use crate::client::SftpgoClientBase;
+use crate::rest_client::{EasyRestSftpgoClient, Named};
+use crate::{Creates, Existing, Result, UserStatus};
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use url::Url;
+
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[repr(u8)]
+pub enum EventTrigger {
+    #[default]
+    FilesystemEvent = 1,
+    ProviderEvent = 2,
+    Schedule = 3,
+    IpBlocked = 4,
+    CertificateRenewal = 5,
+    OnDemand = 6,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct Schedule {
+    pub hour: String,
+    pub day_of_week: String,
+    pub day_of_month: String,
+    pub month: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct ConditionPattern {
+    pub pattern: String,
+    pub inverse_match: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct EventConditionOptions {
+    pub names: Option<Vec<ConditionPattern>>,
+    pub group_names: Option<Vec<ConditionPattern>>,
+    pub role_names: Option<Vec<ConditionPattern>>,
+    pub fs_paths: Option<Vec<ConditionPattern>>,
+    pub protocols: Option<Vec<String>>,
+    pub provider_objects: Option<Vec<String>>,
+    pub min_file_size: Option<i64>,
+    pub max_file_size: Option<i64>,
+    pub concurrent_execution: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct EventConditions {
+    pub fs_events: Option<Vec<String>>,
+    pub provider_events: Option<Vec<String>>,
+    pub schedules: Option<Vec<Schedule>>,
+    pub options: Option<EventConditionOptions>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct EventActionMappingOptions {
+    pub is_failure_action: Option<bool>,
+    pub stop_on_failure: Option<bool>,
+    pub execute_sync: Option<bool>,
+}
+
+/// The reference to an event action from a rule
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct EventActionMapping {
+    pub name: String,
+    pub order: i32,
+    pub options: Option<EventActionMappingOptions>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct EventRuleRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub status: Option<UserStatus>,
+    pub trigger: EventTrigger,
+    pub conditions: EventConditions,
+    pub actions: Vec<EventActionMapping>,
+}
+
+impl Named for EventRuleRequest {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Creates<EventRuleResponse> for EventRuleRequest {}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct EventRuleResponse {
+    pub id: i32,
+    pub name: String,
+    pub description: Option<String>,
+    pub status: UserStatus,
+    pub trigger: EventTrigger,
+    pub conditions: Option<EventConditions>,
+    pub actions: Option<Vec<EventActionMapping>>,
+}
+
+impl Existing for EventRuleResponse {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+}
+
+impl<Client> EasyRestSftpgoClient<EventRuleRequest, EventRuleResponse> for Client
+where
+    Client: SftpgoClientBase,
+{
+    fn get_url(&self, path: Option<&str>) -> Result<Url> {
+        if let Some(path) = path {
+            self.url_for(&format!("/api/v2/eventrules/{}", path))
+        } else {
+            self.url_for("/api/v2/eventrules")
+        }
+    }
+}
@@ -70,12 +70,93 @@ pub struct FileSystemConfigAzureBlobStorage {
     pub use_emulator: Option<bool>,
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct FileSystemConfigS3 {
+    pub bucket: String,
+    pub region: String,
+    pub endpoint: Option<String>,
+    pub access_key: Option<String>,
+    pub access_secret: Option<SftpgoSecret>,
+    pub role_arn: Option<String>,
+    pub storage_class: Option<String>,
+    pub acl: Option<String>,
+    pub upload_part_size: Option<i32>,
+    pub upload_concurrency: Option<i32>,
+    pub upload_part_max_time: Option<i32>,
+    pub download_part_size: Option<i32>,
+    pub download_concurrency: Option<i32>,
+    pub download_part_max_time: Option<i32>,
+    pub force_path_style: Option<bool>,
+    pub key_prefix: Option<String>,
+}
+
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Eq, Debug, Clone, Default)]
+#[repr(u8)]
+pub enum GoogleCloudStorageAutomaticCredentials {
+    #[default]
+    Disabled = 0,
+    Enabled = 1,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct FileSystemConfigGoogleCloudStorage {
+    pub bucket: String,
+    /// the content of the service account JSON key
+    pub credentials: Option<SftpgoSecret>,
+    pub automatic_credentials: GoogleCloudStorageAutomaticCredentials,
+    pub storage_class: Option<String>,
+    pub acl: Option<String>,
+    pub upload_part_size: Option<i32>,
+    pub upload_part_max_time: Option<i32>,
+    pub key_prefix: Option<String>,
+}
+
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Eq, Debug, Clone, Default)]
+#[repr(u8)]
+pub enum FileSystemEqualityCheckMode {
+    #[default]
+    EndpointAndUsername = 0,
+    Endpoint = 1,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct FileSystemConfigSftp {
+    pub endpoint: String,
+    pub username: String,
+    pub password: Option<SftpgoSecret>,
+    pub private_key: Option<SftpgoSecret>,
+    pub key_passphrase: Option<SftpgoSecret>,
+    pub fingerprints: Option<Vec<String>>,
+    pub prefix: Option<String>,
+    pub disable_concurrent_reads: Option<bool>,
+    /// buffer size in MB, 0 disables buffering
+    pub buffer_size: Option<i32>,
+    pub equality_check_mode: FileSystemEqualityCheckMode,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct FileSystemConfigHttp {
+    pub endpoint: String,
+    pub username: Option<String>,
+    pub password: Option<SftpgoSecret>,
+    pub api_key: Option<SftpgoSecret>,
+    pub skip_tls_verify: Option<bool>,
+    pub equality_check_mode: FileSystemEqualityCheckMode,
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
 pub struct FileSystemOsConfig {
     pub read_buffer_size: Option<i32>,
     pub write_buffer_size: Option<i32>,
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct FileSystemCryptConfig {
+    pub passphrase: SftpgoSecret,
+    pub read_buffer_size: Option<i32>,
+    pub write_buffer_size: Option<i32>,
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
 pub struct FileSystem {
     pub provider: FileSystemProvider,
@@ -84,4 +165,14 @@ pub struct FileSystem {
     pub os_config: Option<FileSystemOsConfig>,
     #[serde(rename = "azblobconfig")]
     pub az_blob_config: Option<FileSystemConfigAzureBlobStorage>,
+    #[serde(rename = "s3config")]
+    pub s3_config: Option<FileSystemConfigS3>,
+    #[serde(rename = "gcsconfig")]
+    pub gcs_config: Option<FileSystemConfigGoogleCloudStorage>,
+    #[serde(rename = "sftpconfig")]
+    pub sftp_config: Option<FileSystemConfigSftp>,
+    #[serde(rename = "httpconfig")]
+    pub http_config: Option<FileSystemConfigHttp>,
+    #[serde(rename = "cryptconfig")]
+    pub crypt_config: Option<FileSystemCryptConfig>,
 }
@@ -28,6 +28,8 @@ pub struct FolderResponse {
     pub mapped_path: Option<String>,
     pub description: Option<String>,
     pub filesystem: FileSystem,
+    pub used_quota_size: Option<i64>,
+    pub used_quota_files: Option<i32>,
 }
 
 impl Existing for FolderResponse {
@@ -0,0 +1,105 @@
+use crate::client::SftpgoClientBase;
+use crate::filesystem::FileSystem;
+use crate::rest_client::{EasyRestSftpgoClient, Named};
+use crate::virtual_folder_reference::VirtualFolderReference;
+use crate::{Creates, Existing, Result};
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use std::collections::HashMap;
+use url::Url;
+
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[repr(u8)]
+pub enum GroupType {
+    #[default]
+    Primary = 1,
+    Secondary = 2,
+    Membership = 3,
+}
+
+/// The reference to a group from a user
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct GroupMapping {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub group_type: GroupType,
+}
+
+/// Settings applied to every member of the group
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct GroupUserSettings {
+    pub home_dir: Option<String>,
+    pub max_sessions: Option<i32>,
+    pub quota_size: Option<i64>,
+    pub quota_files: Option<i32>,
+    pub permissions: Option<HashMap<String, Vec<String>>>,
+    pub upload_bandwidth: Option<i64>,
+    pub download_bandwidth: Option<i64>,
+    pub filesystem: Option<FileSystem>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct GroupRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub user_settings: Option<GroupUserSettings>,
+    pub virtual_folders: Option<Vec<VirtualFolderReference>>,
+}
+
+impl Named for GroupRequest {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Creates<GroupResponse> for GroupRequest {}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct GroupResponse {
+    pub id: i32,
+    pub name: String,
+    pub description: Option<String>,
+    pub user_settings: Option<GroupUserSettings>,
+    pub virtual_folders: Option<Vec<VirtualFolderReference>>,
+    pub users: Option<Vec<String>>,
+}
+
+impl Existing for GroupResponse {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+}
+
+impl<Client> EasyRestSftpgoClient<GroupRequest, GroupResponse> for Client
+where
+    Client: SftpgoClientBase,
+{
+    fn get_url(&self, path: Option<&str>) -> Result<Url> {
+        if let Some(path) = path {
+            self.url_for(&format!("/api/v2/groups/{}", path))
+        } else {
+            self.url_for("/api/v2/groups")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GroupMapping, GroupType};
+
+    #[test]
+    fn group_mapping_serializes_type_as_number() {
+        let mapping = GroupMapping {
+            name: "partners".to_string(),
+            group_type: GroupType::Secondary,
+        };
+
+        let json = serde_json::to_value(&mapping).unwrap();
+
+        assert_eq!(json, serde_json::json!({"name": "partners", "type": 2}));
+    }
+}
@@ -0,0 +1,175 @@
+use crate::error_response::{handle_response, Result};
+use crate::{AuthorizedSftpgoClientBase, GenericResponseBody};
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use url::Url;
+
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[repr(u8)]
+pub enum IpListType {
+    #[default]
+    Allowlist = 1,
+    Defender = 2,
+    RateLimiterSafeList = 3,
+}
+
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[repr(u8)]
+pub enum IpListMode {
+    #[default]
+    Allow = 1,
+    /// Only valid for the defender list
+    Deny = 2,
+}
+
+/// Bit flags for the protocols an entry applies to. 0 means all protocols
+pub const IP_LIST_PROTOCOL_SSH: i32 = 1;
+pub const IP_LIST_PROTOCOL_FTP: i32 = 2;
+pub const IP_LIST_PROTOCOL_WEBDAV: i32 = 4;
+pub const IP_LIST_PROTOCOL_HTTP: i32 = 8;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct IpListEntryRequest {
+    /// IP address or network in CIDR notation, for example "192.168.1.0/24"
+    pub ipornet: String,
+    pub description: Option<String>,
+    pub mode: IpListMode,
+    pub protocols: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct IpListEntryResponse {
+    pub ipornet: String,
+    pub description: Option<String>,
+    #[serde(rename = "type")]
+    pub list_type: IpListType,
+    pub mode: IpListMode,
+    pub protocols: i32,
+    pub created_at: Option<i64>,
+    pub updated_at: Option<i64>,
+}
+
+/// Ip list entries are identified by the list type and the ip or network, so they don't fit
+/// the generic rest client.
+#[async_trait]
+pub trait IpListClient: AuthorizedSftpgoClientBase {
+    fn ip_list_url(&self, list_type: IpListType, ipornet: Option<&str>) -> Result<Url> {
+        if let Some(ipornet) = ipornet {
+            // The network contains a slash, which has to be escaped to be part of the path
+            self.url_for(&format!(
+                "/api/v2/iplists/{}/{}",
+                list_type as u8,
+                ipornet.replace('/', "%2F")
+            ))
+        } else {
+            self.url_for(&format!("/api/v2/iplists/{}", list_type as u8))
+        }
+    }
+
+    async fn create_ip_list_entry(
+        &self,
+        list_type: IpListType,
+        item: &IpListEntryRequest,
+    ) -> Result<GenericResponseBody> {
+        let url = self.ip_list_url(list_type, None)?;
+
+        let auth_context = self.get_auth_context();
+        let auth_header_value = auth_context.get_auth_header_value().await?;
+        let res = self
+            .get_client()
+            .post(url)
+            .header(auth_context.get_auth_header_name(), auth_header_value)
+            .json(&item)
+            .send()
+            .await?;
+
+        handle_response(res).await
+    }
+
+    async fn update_ip_list_entry(
+        &self,
+        list_type: IpListType,
+        item: &IpListEntryRequest,
+    ) -> Result<GenericResponseBody> {
+        let url = self.ip_list_url(list_type, Some(&item.ipornet))?;
+
+        let auth_context = self.get_auth_context();
+        let auth_header_value = auth_context.get_auth_header_value().await?;
+        let res = self
+            .get_client()
+            .put(url)
+            .header(auth_context.get_auth_header_name(), auth_header_value)
+            .json(&item)
+            .send()
+            .await?;
+
+        handle_response(res).await
+    }
+
+    async fn get_ip_list_entry(
+        &self,
+        list_type: IpListType,
+        ipornet: &str,
+    ) -> Result<Option<IpListEntryResponse>> {
+        let url = self.ip_list_url(list_type, Some(ipornet))?;
+
+        let auth_context = self.get_auth_context();
+        let auth_header_value = auth_context.get_auth_header_value().await?;
+        let res = self
+            .get_client()
+            .get(url)
+            .header(auth_context.get_auth_header_name(), auth_header_value)
+            .send()
+            .await?;
+
+        if res.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        handle_response(res).await
+    }
+
+    async fn delete_ip_list_entry(&self, list_type: IpListType, ipornet: &str) -> Result<()> {
+        let url = self.ip_list_url(list_type, Some(ipornet))?;
+
+        let auth_context = self.get_auth_context();
+        let auth_header_value = auth_context.get_auth_header_value().await?;
+        let res = self
+            .get_client()
+            .delete(url)
+            .header(auth_context.get_auth_header_name(), auth_header_value)
+            .send()
+            .await?;
+
+        if res.status() == StatusCode::NOT_FOUND || res.status() == StatusCode::OK {
+            return Ok(());
+        }
+
+        handle_response(res).await
+    }
+}
+
+impl<T> IpListClient for T where T: AuthorizedSftpgoClientBase {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ApiKeyAuthContext, SftpgoClient};
+
+    #[test]
+    fn ip_list_url_escapes_network() {
+        let client = SftpgoClient::new(Url::parse("http://localhost:8080").unwrap())
+            .with_auth_context(ApiKeyAuthContext::new("key".to_string()));
+
+        let url = client
+            .ip_list_url(IpListType::Defender, Some("192.168.1.0/24"))
+            .unwrap();
+
+        assert_eq!(
+            url.as_str(),
+            "http://localhost:8080/api/v2/iplists/2/192.168.1.0%2F24"
+        );
+    }
+}
@@ -1,19 +1,33 @@
 mod admin_token;
 pub mod admins;
+mod api_key_auth;
+pub mod api_keys;
 mod auth;
 mod client;
+pub mod connections;
 mod error_response;
+pub mod event_actions;
+pub mod event_rules;
 pub mod filesystem;
 pub mod folders;
+pub mod groups;
+pub mod ip_lists;
 mod rest_client;
+pub mod roles;
+pub mod shares;
+mod user_token;
 pub mod users;
 pub mod virtual_folder_reference;
 
 pub use admin_token::*;
+pub use api_key_auth::ApiKeyAuthContext;
 pub use auth::AuthContext;
-pub use client::{AuthorizedSftpgoClient, AuthorizedSftpgoClientBase, SftpgoClient};
+pub use client::{
+    AuthorizedSftpgoClient, AuthorizedSftpgoClientBase, SftpgoClient, SftpgoClientBase,
+};
 pub use error_response::*;
 pub use rest_client::{
     CreatedFrom, Creates, EasyRestSftpgoClient, Existing, Named, SftpgoRestClient,
 };
-pub use users::{UserRequest, UserResponse, UserStatus};
+pub use user_token::*;
+pub use users::{UserFilters, UserRequest, UserResponse, UserStatus};
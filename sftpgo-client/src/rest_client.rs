@@ -1,8 +1,8 @@
 use crate::{handle_response, AuthorizedSftpgoClientBase, GenericResponseBody};
 use async_trait::async_trait;
-use reqwest::header::AUTHORIZATION;
 use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use url::Url;
 
 pub trait Named {
@@ -29,6 +29,13 @@ where
     async fn update(&self, item: &TRequest) -> crate::Result<GenericResponseBody>;
     async fn delete(&self, name: &str) -> crate::Result<()>;
     async fn get(&self, name: &str) -> crate::Result<Option<TResponse>>;
+
+    /// Gets the resource as returned by SFTPGo, including fields the response type doesn't know
+    async fn get_raw(&self, name: &str) -> crate::Result<Option<Value>>;
+
+    /// Updates the resource with an untyped body, for example the result of `get_raw` with
+    /// changes applied
+    async fn update_raw(&self, name: &str, item: &Value) -> crate::Result<GenericResponseBody>;
 }
 
 pub trait EasyRestSftpgoClient<TRequest, TResponse> {
@@ -45,11 +52,12 @@ where
     async fn create(&self, item: &TRequest) -> crate::Result<TResponse> {
         let url = self.get_url(None)?;
 
-        let auth_header_value = self.get_auth_context().get_auth_header_value().await?;
+        let auth_context = self.get_auth_context();
+        let auth_header_value = auth_context.get_auth_header_value().await?;
         let res = self
             .get_client()
             .post(url)
-            .header(AUTHORIZATION, auth_header_value)
+            .header(auth_context.get_auth_header_name(), auth_header_value)
             .json(&item)
             .send()
             .await?;
@@ -60,11 +68,12 @@ where
     async fn update(&self, item: &TRequest) -> crate::Result<GenericResponseBody> {
         let url = self.get_url(Some(item.name()))?;
 
-        let auth_header_value = self.get_auth_context().get_auth_header_value().await?;
+        let auth_context = self.get_auth_context();
+        let auth_header_value = auth_context.get_auth_header_value().await?;
         let res = self
             .get_client()
             .put(url)
-            .header(AUTHORIZATION, auth_header_value)
+            .header(auth_context.get_auth_header_name(), auth_header_value)
             .json(&item)
             .send()
             .await?;
@@ -72,14 +81,31 @@ where
         handle_response(res).await
     }
 
+    async fn update_raw(&self, name: &str, item: &Value) -> crate::Result<GenericResponseBody> {
+        let url = self.get_url(Some(name))?;
+
+        let auth_context = self.get_auth_context();
+        let auth_header_value = auth_context.get_auth_header_value().await?;
+        let res = self
+            .get_client()
+            .put(url)
+            .header(auth_context.get_auth_header_name(), auth_header_value)
+            .json(item)
+            .send()
+            .await?;
+
+        handle_response(res).await
+    }
+
     async fn delete(&self, name: &str) -> crate::Result<()> {
         let url = self.get_url(Some(name))?;
 
-        let auth_header_value = self.get_auth_context().get_auth_header_value().await?;
+        let auth_context = self.get_auth_context();
+        let auth_header_value = auth_context.get_auth_header_value().await?;
         let res = self
             .get_client()
             .delete(url)
-            .header(AUTHORIZATION, auth_header_value)
+            .header(auth_context.get_auth_header_name(), auth_header_value)
             .send()
             .await?;
 
@@ -91,13 +117,21 @@ where
     }
 
     async fn get(&self, name: &str) -> crate::Result<Option<TResponse>> {
+        match self.get_raw(name).await? {
+            Some(value) => Ok(Some(serde_json::from_value(value)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn get_raw(&self, name: &str) -> crate::Result<Option<Value>> {
         let url = self.get_url(Some(name))?;
 
-        let auth_header_value = self.get_auth_context().get_auth_header_value().await?;
+        let auth_context = self.get_auth_context();
+        let auth_header_value = auth_context.get_auth_header_value().await?;
         let res = self
             .get_client()
             .get(url)
-            .header(AUTHORIZATION, auth_header_value)
+            .header(auth_context.get_auth_header_name(), auth_header_value)
             .send()
             .await?;
 
@@ -0,0 +1,51 @@
+use crate::client::SftpgoClientBase;
+use crate::rest_client::{EasyRestSftpgoClient, Named};
+use crate::{Creates, Existing, Result};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct RoleRequest {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+impl Named for RoleRequest {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Creates<RoleResponse> for RoleRequest {}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct RoleResponse {
+    pub id: i32,
+    pub name: String,
+    pub description: Option<String>,
+    pub users: Option<Vec<String>>,
+    pub admins: Option<Vec<String>>,
+}
+
+impl Existing for RoleResponse {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+}
+
+impl<Client> EasyRestSftpgoClient<RoleRequest, RoleResponse> for Client
+where
+    Client: SftpgoClientBase,
+{
+    fn get_url(&self, path: Option<&str>) -> Result<Url> {
+        if let Some(path) = path {
+            self.url_for(&format!("/api/v2/roles/{}", path))
+        } else {
+            self.url_for("/api/v2/roles")
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use crate::error_response::{handle_response, Result};
+use crate::{AuthorizedSftpgoClientBase, GenericResponseBody};
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[repr(u8)]
+pub enum ShareScope {
+    #[default]
+    Read = 1,
+    Write = 2,
+    ReadWrite = 3,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct ShareRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub scope: ShareScope,
+    /// virtual paths to share. For the write scope only a single directory is allowed
+    pub paths: Vec<String>,
+    /// expiration time as unix timestamp in milliseconds. 0 means no expiration
+    pub expires_at: Option<i64>,
+    /// optional password to protect the share. The server stores it hashed
+    pub password: Option<String>,
+    /// maximum number of times the share can be used. 0 means no limit
+    pub max_tokens: Option<i32>,
+    /// IP addresses or networks allowed to use the share, in CIDR notation
+    pub allow_from: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct ShareResponse {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub scope: ShareScope,
+    pub paths: Option<Vec<String>>,
+    pub username: Option<String>,
+    pub created_at: Option<i64>,
+    pub updated_at: Option<i64>,
+    pub last_use_at: Option<i64>,
+    pub expires_at: Option<i64>,
+    pub max_tokens: Option<i32>,
+    pub used_tokens: Option<i32>,
+    pub allow_from: Option<Vec<String>>,
+}
+
+/// Shares belong to a user and are managed through the user REST API, so the client must be
+/// authorized as that user. Shares are identified by their generated id.
+#[async_trait]
+pub trait ShareClient: AuthorizedSftpgoClientBase {
+    /// Creates the share and returns the id of the new share
+    async fn create_share(&self, item: &ShareRequest) -> Result<String> {
+        let url = self.url_for("/api/v2/user/shares")?;
+
+        let auth_context = self.get_auth_context();
+        let auth_header_value = auth_context.get_auth_header_value().await?;
+        let res = self
+            .get_client()
+            .post(url)
+            .header(auth_context.get_auth_header_name(), auth_header_value)
+            .json(&item)
+            .send()
+            .await?;
+
+        let id = res
+            .headers()
+            .get("X-Object-ID")
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string());
+
+        // The location header points at the new share, so the id can be recovered if the
+        // object id header is missing
+        let location = res
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.rsplit('/').next())
+            .map(|v| v.to_string());
+
+        let _: GenericResponseBody = handle_response(res).await?;
+
+        Ok(id.or(location).unwrap_or_default())
+    }
+
+    async fn update_share(&self, id: &str, item: &ShareRequest) -> Result<GenericResponseBody> {
+        let url = self.url_for(&format!("/api/v2/user/shares/{}", id))?;
+
+        let auth_context = self.get_auth_context();
+        let auth_header_value = auth_context.get_auth_header_value().await?;
+        let res = self
+            .get_client()
+            .put(url)
+            .header(auth_context.get_auth_header_name(), auth_header_value)
+            .json(&item)
+            .send()
+            .await?;
+
+        handle_response(res).await
+    }
+
+    async fn get_share(&self, id: &str) -> Result<Option<ShareResponse>> {
+        let url = self.url_for(&format!("/api/v2/user/shares/{}", id))?;
+
+        let auth_context = self.get_auth_context();
+        let auth_header_value = auth_context.get_auth_header_value().await?;
+        let res = self
+            .get_client()
+            .get(url)
+            .header(auth_context.get_auth_header_name(), auth_header_value)
+            .send()
+            .await?;
+
+        if res.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        handle_response(res).await
+    }
+
+    async fn delete_share(&self, id: &str) -> Result<()> {
+        let url = self.url_for(&format!("/api/v2/user/shares/{}", id))?;
+
+        let auth_context = self.get_auth_context();
+        let auth_header_value = auth_context.get_auth_header_value().await?;
+        let res = self
+            .get_client()
+            .delete(url)
+            .header(auth_context.get_auth_header_name(), auth_header_value)
+            .send()
+            .await?;
+
+        if res.status() == StatusCode::NOT_FOUND || res.status() == StatusCode::OK {
+            return Ok(());
+        }
+
+        handle_response(res).await
+    }
+}
+
+impl<T> ShareClient for T where T: AuthorizedSftpgoClientBase {}
@@ -0,0 +1,124 @@
+use crate::auth::{create_basic_auth_header, create_bearer_auth_header, AuthContext};
+use crate::client::SftpgoClientBase;
+use crate::error_response::{handle_response, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use log::{debug, trace};
+use reqwest::header::AUTHORIZATION;
+use serde::Deserialize;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+#[derive(Deserialize)]
+pub struct UserAccessToken {
+    pub access_token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait UserAccessTokenClient: SftpgoClientBase {
+    async fn create_user_access_token(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> Result<UserAccessToken> {
+        let url = self.url_for("/api/v2/user/token")?;
+        let auth_header_value = create_basic_auth_header(username, password);
+        trace!("Sending user auth token request to {}", url);
+        let res = self
+            .get_client()
+            .get(url)
+            .header(AUTHORIZATION, auth_header_value)
+            .send()
+            .await?;
+
+        handle_response(res).await
+    }
+}
+
+impl<T> UserAccessTokenClient for T where T: SftpgoClientBase {}
+
+struct UserAuthInfo {
+    username: String,
+    password: String,
+}
+
+/// Authenticates against the user REST API as a specific user. A new token is requested shortly
+/// before the current one expires, so the context can be kept around between reconciles.
+#[derive(Clone)]
+pub struct RefreshableUserAuthContext<T>
+where
+    T: UserAccessTokenClient + Send + Sync,
+{
+    creds: Arc<UserAuthInfo>,
+    client: T,
+    token: Arc<RwLock<StoredUserAccessToken>>,
+}
+
+impl<T> RefreshableUserAuthContext<T>
+where
+    T: UserAccessTokenClient + Sync + Send,
+{
+    pub async fn new(
+        username: String,
+        password: String,
+        client: T,
+    ) -> Result<RefreshableUserAuthContext<T>> {
+        let initial_token = client
+            .create_user_access_token(&username, &password)
+            .await?;
+
+        Ok(RefreshableUserAuthContext {
+            creds: Arc::new(UserAuthInfo { username, password }),
+            client,
+            token: Arc::new(RwLock::new(StoredUserAccessToken::from(initial_token))),
+        })
+    }
+}
+
+struct StoredUserAccessToken {
+    access_token: String,
+    refresh_at: DateTime<Utc>,
+}
+
+impl From<UserAccessToken> for StoredUserAccessToken {
+    fn from(token: UserAccessToken) -> Self {
+        StoredUserAccessToken {
+            access_token: token.access_token,
+            refresh_at: token.expires_at - chrono::Duration::seconds(30),
+        }
+    }
+}
+
+#[async_trait]
+impl<T> AuthContext for RefreshableUserAuthContext<T>
+where
+    T: UserAccessTokenClient + Sync + Send,
+{
+    async fn get_auth_header_value(&self) -> Result<String> {
+        {
+            let token = self.token.read().await;
+
+            if token.refresh_at > Utc::now() {
+                return Ok(create_bearer_auth_header(&token.access_token));
+            }
+        }
+
+        debug!("User token is about to expire, so we need to refresh it");
+        let mut token = self.token.write().await;
+
+        // Check if another thread already refreshed the token
+        if token.refresh_at > Utc::now() {
+            return Ok(create_bearer_auth_header(&token.access_token));
+        }
+
+        let new_token = self
+            .client
+            .create_user_access_token(&self.creds.username, &self.creds.password)
+            .await?;
+
+        *token = StoredUserAccessToken::from(new_token);
+
+        Ok(create_bearer_auth_header(&token.access_token))
+    }
+}
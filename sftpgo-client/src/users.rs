@@ -1,5 +1,6 @@
 use crate::error_response::Result;
 use crate::filesystem::FileSystem;
+use crate::groups::GroupMapping;
 use crate::rest_client::{Creates, EasyRestSftpgoClient, Named};
 use crate::virtual_folder_reference::VirtualFolderReference;
 use crate::{AuthorizedSftpgoClientBase, Existing};
@@ -16,9 +17,54 @@ pub enum UserStatus {
     Enabled = 1,
 }
 
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[repr(u8)]
+pub enum PatternsDenyPolicy {
+    #[default]
+    Default = 0,
+    /// Hide the denied files from listings
+    Hide = 1,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct PatternsFilter {
+    pub path: String,
+    pub allowed_patterns: Option<Vec<String>>,
+    pub denied_patterns: Option<Vec<String>>,
+    pub deny_policy: Option<PatternsDenyPolicy>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct BandwidthLimit {
+    /// IP addresses or networks in CIDR notation
+    pub sources: Vec<String>,
+    /// KB/s
+    pub upload_bandwidth: Option<i64>,
+    /// KB/s
+    pub download_bandwidth: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct UserFilters {
+    pub allowed_ip: Option<Vec<String>>,
+    pub denied_ip: Option<Vec<String>>,
+    /// For example "publickey", "password", "keyboard-interactive"
+    pub denied_login_methods: Option<Vec<String>>,
+    /// For example "SSH", "FTP", "DAV", "HTTP"
+    pub denied_protocols: Option<Vec<String>>,
+    pub file_patterns: Option<Vec<PatternsFilter>>,
+    /// bytes
+    pub max_upload_file_size: Option<i64>,
+    /// "None" or "CommonName"
+    pub tls_username: Option<String>,
+    pub start_directory: Option<String>,
+    pub two_factor_protocols: Option<Vec<String>>,
+    pub bandwidth_limits: Option<Vec<BandwidthLimit>>,
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
 pub struct UserRequest {
-    pub status: UserStatus,
+    pub status: Option<UserStatus>,
     pub username: String,
     pub email: Option<String>,
     pub description: Option<String>,
@@ -37,8 +83,11 @@ pub struct UserRequest {
     pub upload_data_transfer: Option<i64>,
     pub download_data_transfer: Option<i64>,
     pub total_data_transfer: Option<i64>,
-    pub filesystem: FileSystem,
+    pub filesystem: Option<FileSystem>,
     pub virtual_folders: Option<Vec<VirtualFolderReference>>,
+    pub groups: Option<Vec<GroupMapping>>,
+    pub role: Option<String>,
+    pub filters: Option<UserFilters>,
 }
 
 impl Named for UserRequest {
@@ -72,6 +121,19 @@ pub struct UserResponse {
     pub download_data_transfer: Option<i64>,
     pub total_data_transfer: Option<i64>,
     pub virtual_folders: Option<Vec<VirtualFolderReference>>,
+    pub groups: Option<Vec<GroupMapping>>,
+    pub role: Option<String>,
+    pub filters: Option<UserFilters>,
+    pub used_quota_size: Option<i64>,
+    pub used_quota_files: Option<i32>,
+    /// bytes
+    pub used_upload_data_transfer: Option<i64>,
+    /// bytes
+    pub used_download_data_transfer: Option<i64>,
+    /// unix timestamp in milliseconds, 0 if the user never logged in
+    pub last_login: Option<i64>,
+    /// unix timestamp in milliseconds, 0 if the user never uploaded a file
+    pub first_upload: Option<i64>,
 }
 
 impl Existing for UserResponse {